@@ -0,0 +1,164 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const PREFILL: usize = 10_000;
+const READERS: usize = 4;
+
+/// Storage used before CommandLog
+#[derive(Default)]
+struct VecLog(RwLock<Vec<ProgramCommand>>);
+
+trait Log: Send + Sync + 'static {
+    fn append(&self, command: ProgramCommand);
+    fn export_len(&self, from: usize) -> usize;
+}
+
+impl Log for VecLog {
+    fn append(&self, command: ProgramCommand) {
+        self.0.write().unwrap().push(command);
+    }
+
+    fn export_len(&self, from: usize) -> usize {
+        Vec::from(&self.0.read().unwrap()[from..]).len()
+    }
+}
+
+impl Log for CommandLog {
+    fn append(&self, command: ProgramCommand) {
+        CommandLog::append(self, command);
+    }
+
+    fn export_len(&self, from: usize) -> usize {
+        self.snapshot(from).iter().count()
+    }
+}
+
+fn command(i: usize) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command
+        .mut_PRINT()
+        .set_PRINT_LINE(format!("line {} of long narrative text", i));
+    command
+}
+
+fn prefilled<L: Log + Default>() -> Arc<L> {
+    let log = L::default();
+
+    for i in 0..PREFILL {
+        log.append(command(i));
+    }
+
+    Arc::new(log)
+}
+
+/// Run `f` while background threads keep running `background`
+fn with_background<L: Log>(
+    log: &Arc<L>,
+    threads: usize,
+    background: fn(&L, usize),
+    f: impl FnOnce(),
+) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let log = log.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut i = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    background(&log, i);
+                    i += 1;
+                }
+            })
+        })
+        .collect();
+
+    f();
+
+    stop.store(true, Ordering::Relaxed);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+fn export_with_writer<L: Log + Default>(iters: u64) -> Duration {
+    let log = prefilled::<L>();
+    let mut elapsed = Duration::default();
+
+    with_background(
+        &log,
+        1,
+        |log, i| {
+            log.append(command(i));
+            // keep log size bounded while measuring
+            thread::sleep(Duration::from_micros(10));
+        },
+        || {
+            for _ in 0..iters {
+                let start = Instant::now();
+                black_box(log.export_len(0));
+                elapsed += start.elapsed();
+            }
+        },
+    );
+
+    elapsed
+}
+
+fn append_with_readers<L: Log + Default>(iters: u64) -> Duration {
+    let log = prefilled::<L>();
+    let mut elapsed = Duration::default();
+
+    with_background(
+        &log,
+        READERS,
+        |log, _| {
+            black_box(log.export_len(0));
+        },
+        || {
+            for i in 0..iters {
+                let command = command(i as usize);
+                let start = Instant::now();
+                log.append(command);
+                elapsed += start.elapsed();
+                // game thread does other work between prints
+                thread::sleep(Duration::from_micros(20));
+            }
+        },
+    );
+
+    elapsed
+}
+
+fn bench_export(c: &mut Criterion) {
+    let mut group = c.benchmark_group("export_with_writer");
+    group.bench_function(BenchmarkId::new("rwlock_vec", PREFILL), |b| {
+        b.iter_custom(export_with_writer::<VecLog>)
+    });
+    group.bench_function(BenchmarkId::new("command_log", PREFILL), |b| {
+        b.iter_custom(export_with_writer::<CommandLog>)
+    });
+    group.finish();
+}
+
+fn bench_append(c: &mut Criterion) {
+    let mut group = c.benchmark_group("append_with_readers");
+    group.bench_function(BenchmarkId::new("rwlock_vec", READERS), |b| {
+        b.iter_custom(append_with_readers::<VecLog>)
+    });
+    group.bench_function(BenchmarkId::new("command_log", READERS), |b| {
+        b.iter_custom(append_with_readers::<CommandLog>)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_export, bench_append);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use protobuf::Message;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+fn console(count: usize) -> Arc<ConsoleContext> {
+    let ctx = Arc::new(ConsoleContext::new());
+
+    for i in 0..count {
+        let mut command = ProgramCommand::new();
+        command
+            .mut_PRINT()
+            .set_PRINT_LINE(format!("line {} of long narrative text", i));
+        ctx.append_command(command);
+    }
+
+    ctx
+}
+
+fn get_state() -> ConsoleMessage {
+    let mut msg = ConsoleMessage::new();
+    msg.mut_REQ().set_GET_STATE(0);
+    msg
+}
+
+fn bench_get_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_state");
+
+    for count in [100, 10_000] {
+        let ctx = console(count);
+        let connector = ConnectorContext::new(ctx);
+
+        group.throughput(Throughput::Elements(count as u64));
+
+        // previous path, clone commands with export_command and encode them
+        group.bench_function(BenchmarkId::new("export_command", count), |b| {
+            b.iter(|| {
+                let msg = connector.on_recv_message(get_state()).unwrap();
+                black_box(msg.write_to_bytes().unwrap())
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("cached_bytes", count), |b| {
+            b.iter(|| black_box(connector.on_recv_message_encoded(get_state()).unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_state);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! Terminal frontend for qni programs
+//!
+//! ```text
+//! qni-tty run <library> [entry]
+//! qni-tty connect <address> [session]
+//! ```
+//!
+//! `run` loads program from dynamic library and run it in-process,
+//! entry is `extern "C" fn(ConsoleArcCtx)` named `qni_main` by default.
+//! Program must be built with same version of qni-core-rs.
+//!
+//! `connect` attaches to program served by `transport::serve_tcp`,
+//! or to console of session served by `transport::serve_registry`.
+
+use std::env;
+use std::error::Error;
+use std::mem;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+
+use qni_core_rs::c_api::ConsoleArcCtx;
+use qni_core_rs::prelude::*;
+use qni_core_rs::transport::{LocalTransport, StreamTransport};
+use qni_core_rs::tty::TtyFrontend;
+
+const USAGE: &str = "usage: qni-tty run <library> [entry]
+       qni-tty connect <address> [session]";
+
+type EntryFn = unsafe extern "C" fn(ConsoleArcCtx);
+
+fn run_library(path: &str, entry: &str) -> Result<(), Box<dyn Error>> {
+    let library = unsafe { libloading::Library::new(path)? };
+    let entry: EntryFn = unsafe { *library.get::<EntryFn>(entry.as_bytes())? };
+
+    let ctx = Arc::new(ConsoleContext::new());
+
+    {
+        let mut ctx = ctx.clone();
+        thread::spawn(move || {
+            unsafe {
+                entry(&mut ctx as _);
+            }
+            ctx.set_exit();
+        });
+    }
+
+    let ret = TtyFrontend::new(LocalTransport::new(ctx.clone())).run();
+
+    ctx.set_exit();
+
+    // program thread can be still running
+    mem::forget(library);
+
+    Ok(ret?)
+}
+
+fn connect(addr: &str, session: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let transport = match session {
+        Some(session) => StreamTransport::connect_session(addr, session)?,
+        None => StreamTransport::connect(addr)?,
+    };
+
+    TtyFrontend::new(transport).run()?;
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let ret = match args.as_slice() {
+        ["run", path] => run_library(path, "qni_main"),
+        ["run", path, entry] => run_library(path, entry),
+        ["connect", addr] => connect(addr, None),
+        ["connect", addr, session] => connect(addr, Some(session)),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = ret {
+        eprintln!("qni-tty: {}", err);
+        process::exit(1);
+    }
+
+    process::exit(0);
+}
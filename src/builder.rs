@@ -0,0 +1,384 @@
+//! Constructors and conversions of generated protobuf types
+//!
+//! ```
+//! use qni_core_rs::prelude::*;
+//! use qni_core_rs::prelude::qni_api::*;
+//! use std::time::Duration;
+//!
+//! let commands = vec![
+//!     ProgramCommand::text_color(Color::RED),
+//!     ProgramCommand::print_line("Continue?"),
+//!     ProgramCommand::button("[Yes]", true),
+//! ];
+//! let req = ProgramRequest::input_bool().expire_in(Duration::from_secs(10));
+//! ```
+
+use chrono::prelude::*;
+use protobuf::well_known_types::{Duration as ProtoDuration, Timestamp};
+use protobuf::RepeatedField;
+use std::time::Duration;
+
+use crate::color::Color;
+use crate::font::FontStyles;
+use crate::protos::qni_api::*;
+
+impl ProgramCommand {
+    /// PRINT command
+    pub fn print(text: impl Into<String>) -> Self {
+        let mut data = ConsolePrintData::new();
+        data.set_PRINT(text.into());
+        data.into()
+    }
+
+    /// PRINT_LINE command
+    pub fn print_line(text: impl Into<String>) -> Self {
+        let mut data = ConsolePrintData::new();
+        data.set_PRINT_LINE(text.into());
+        data.into()
+    }
+
+    /// PRINT_BUTTON command
+    pub fn button(text: impl Into<String>, value: impl Into<InputResponse>) -> Self {
+        let mut button = ConsolePrintButtonData::new();
+        button.set_text(text.into());
+        button.set_value(value.into());
+
+        let mut data = ConsolePrintData::new();
+        data.set_PRINT_BUTTON(button);
+        data.into()
+    }
+
+    /// NEW_LINE command
+    pub fn new_line() -> Self {
+        let mut data = ConsolePrintData::new();
+        data.mut_NEW_LINE();
+        data.into()
+    }
+
+    /// DRAW_LINE command
+    pub fn draw_line() -> Self {
+        let mut data = ConsolePrintData::new();
+        data.mut_DRAW_LINE();
+        data.into()
+    }
+
+    /// DELETE_LINE command
+    pub fn delete_line(count: u32) -> Self {
+        let mut data = ConsolePrintData::new();
+        data.set_DELETE_LINE(count);
+        data.into()
+    }
+
+    /// CLEAR_LINE command
+    pub fn clear_line() -> Self {
+        let mut data = ConsolePrintData::new();
+        data.mut_CLEAR_LINE();
+        data.into()
+    }
+
+    /// TEXT_COLOR setting command
+    pub fn text_color(color: Color) -> Self {
+        let mut item = ConsoleSettingItem::new();
+        item.set_TEXT_COLOR(color.into());
+        item.into()
+    }
+
+    /// BACK_COLOR setting command
+    pub fn back_color(color: Color) -> Self {
+        let mut item = ConsoleSettingItem::new();
+        item.set_BACK_COLOR(color.into());
+        item.into()
+    }
+
+    /// HIGHLIGHT_COLOR setting command
+    pub fn highlight_color(color: Color) -> Self {
+        let mut item = ConsoleSettingItem::new();
+        item.set_HIGHLIGHT_COLOR(color.into());
+        item.into()
+    }
+
+    /// FONT setting command
+    pub fn font(font: Font) -> Self {
+        let mut item = ConsoleSettingItem::new();
+        item.set_FONT(font);
+        item.into()
+    }
+
+    /// TEXT_ALIGN setting command
+    pub fn text_align(align: TextAlign) -> Self {
+        let mut item = ConsoleSettingItem::new();
+        item.set_TEXT_ALIGN(align);
+        item.into()
+    }
+}
+
+impl From<ConsolePrintData> for ProgramCommand {
+    fn from(data: ConsolePrintData) -> Self {
+        let mut command = ProgramCommand::new();
+        command.set_PRINT(data);
+        command
+    }
+}
+
+impl From<ConsoleSettingItem> for ProgramCommand {
+    fn from(item: ConsoleSettingItem) -> Self {
+        let mut command = ProgramCommand::new();
+        command.set_UPDATE_SETTING(item);
+        command
+    }
+}
+
+impl Font {
+    /// Create font with REGULAR style
+    pub fn with_family(family: impl Into<String>, size: f32) -> Self {
+        let mut font = Font::new();
+        font.set_font_family(family.into());
+        font.set_font_size(size);
+        font
+    }
+
+    /// Set style, `FontStyle` or combined `FontStyles`
+    pub fn style(mut self, style: impl Into<FontStyles>) -> Self {
+        self.set_font_style(style.into().bits());
+        self
+    }
+}
+
+fn input(f: impl FnOnce(&mut InputRequest)) -> ProgramRequest {
+    let mut req = ProgramRequest::new();
+    f(req.mut_INPUT());
+    req
+}
+
+impl ProgramRequest {
+    /// TOUCH request
+    pub fn input_touch() -> Self {
+        input(|input| {
+            input.mut_TOUCH();
+        })
+    }
+
+    /// ENTER request
+    pub fn input_enter() -> Self {
+        input(|input| {
+            input.mut_ENTER();
+        })
+    }
+
+    /// ANYKEY request
+    pub fn input_anykey() -> Self {
+        input(|input| {
+            input.mut_ANYKEY();
+        })
+    }
+
+    /// BOOLEAN request
+    pub fn input_bool() -> Self {
+        input(|input| {
+            input.mut_BOOLEAN();
+        })
+    }
+
+    /// STR request
+    pub fn input_str() -> Self {
+        input(|input| {
+            input.mut_STR();
+        })
+    }
+
+    /// STR_MAX_LEN request
+    pub fn input_str_max_len(max_len: u32) -> Self {
+        input(|input| input.set_STR_MAX_LEN(max_len))
+    }
+
+    /// STR_SELECT request
+    pub fn input_select<S: Into<String>>(options: impl IntoIterator<Item = S>) -> Self {
+        let mut array = StringArray::new();
+        array.set_data(RepeatedField::from_vec(
+            options.into_iter().map(Into::into).collect(),
+        ));
+        input(|input| input.set_STR_SELECT(array))
+    }
+
+    /// INT request
+    pub fn input_int() -> Self {
+        input(|input| {
+            input.mut_INT();
+        })
+    }
+
+    /// INT_MAX_LEN request
+    pub fn input_int_max_len(max_len: u32) -> Self {
+        input(|input| input.set_INT_MAX_LEN(max_len))
+    }
+
+    /// FLOAT request
+    pub fn input_float() -> Self {
+        input(|input| {
+            input.mut_FLOAT();
+        })
+    }
+
+    /// FLOAT_MAX_LEN request
+    pub fn input_float_max_len(max_len: u32) -> Self {
+        input(|input| input.set_FLOAT_MAX_LEN(max_len))
+    }
+
+    /// DATE request
+    pub fn input_date() -> Self {
+        input(|input| {
+            input.mut_DATE();
+        })
+    }
+
+    /// DATETIME request
+    pub fn input_datetime() -> Self {
+        input(|input| {
+            input.mut_DATETIME();
+        })
+    }
+
+    /// TIME request
+    pub fn input_time() -> Self {
+        input(|input| {
+            input.mut_TIME();
+        })
+    }
+
+    /// COLOR request
+    pub fn input_color() -> Self {
+        input(|input| {
+            input.mut_COLOR();
+        })
+    }
+
+    /// Set expire at wall clock time
+    pub fn expire_at(mut self, time: DateTime<Utc>) -> Self {
+        self.mut_INPUT().set_expire(to_timestamp(time));
+        self
+    }
+
+    /// Set expire after duration from now
+    ///
+    /// `WaitOptions::timeout` uses clock of ConsoleContext instead
+    pub fn expire_in(self, duration: Duration) -> Self {
+        let expire = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| Utc::now().checked_add_signed(duration))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.expire_at(expire)
+    }
+}
+
+impl From<InputRequest> for ProgramRequest {
+    fn from(input: InputRequest) -> Self {
+        let mut req = ProgramRequest::new();
+        req.set_INPUT(input);
+        req
+    }
+}
+
+impl InputResponse {
+    /// EMPTY response
+    pub fn empty() -> Self {
+        let mut res = InputResponse::new();
+        res.mut_EMPTY();
+        res
+    }
+
+    /// DATE response
+    pub fn date(date: NaiveDate) -> Self {
+        let mut res = InputResponse::new();
+        res.set_DATE(to_timestamp(date.and_hms_opt(0, 0, 0).unwrap().and_utc()));
+        res
+    }
+
+    /// DATETIME response
+    pub fn datetime(time: DateTime<Utc>) -> Self {
+        let mut res = InputResponse::new();
+        res.set_DATETIME(to_timestamp(time));
+        res
+    }
+
+    /// TIME response, duration since midnight
+    pub fn time(since_midnight: Duration) -> Self {
+        let mut duration = ProtoDuration::new();
+        duration.set_seconds(since_midnight.as_secs() as i64);
+        duration.set_nanos(since_midnight.subsec_nanos() as i32);
+
+        let mut res = InputResponse::new();
+        res.set_TIME(duration);
+        res
+    }
+}
+
+impl From<bool> for InputResponse {
+    fn from(value: bool) -> Self {
+        let mut res = InputResponse::new();
+        res.set_BOOLEAN(value);
+        res
+    }
+}
+
+impl From<i32> for InputResponse {
+    fn from(value: i32) -> Self {
+        let mut res = InputResponse::new();
+        res.set_INT(value);
+        res
+    }
+}
+
+impl From<f32> for InputResponse {
+    fn from(value: f32) -> Self {
+        let mut res = InputResponse::new();
+        res.set_FLOAT(value);
+        res
+    }
+}
+
+impl From<String> for InputResponse {
+    fn from(value: String) -> Self {
+        let mut res = InputResponse::new();
+        res.set_STR(value);
+        res
+    }
+}
+
+impl From<&str> for InputResponse {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<Color> for InputResponse {
+    fn from(value: Color) -> Self {
+        let mut res = InputResponse::new();
+        res.set_COLOR(value.into());
+        res
+    }
+}
+
+impl ConsoleMessage {
+    /// GET_STATE request from command index
+    pub fn get_state(from: usize) -> Self {
+        let mut msg = ConsoleMessage::new();
+        msg.mut_REQ().set_GET_STATE(from as u64);
+        msg
+    }
+
+    /// Input response of request tag
+    pub fn input_response(tag: u32, value: impl Into<InputResponse>) -> Self {
+        let mut msg = ConsoleMessage::new();
+        let res = msg.mut_RES();
+        res.set_tag(tag);
+        res.set_OK_INPUT(value.into());
+        msg
+    }
+}
+
+pub(crate) fn to_timestamp(time: DateTime<Utc>) -> Timestamp {
+    let mut timestamp = Timestamp::new();
+    timestamp.set_seconds(time.timestamp());
+    timestamp.set_nanos(time.timestamp_subsec_nanos() as i32);
+    timestamp
+}
@@ -1,9 +1,17 @@
-use crate::console::{ConsoleContext, WaitError};
+#![allow(clippy::missing_safety_doc)]
+
+use crate::color::Color;
+use crate::console::{CancelToken, ConsoleContext, WaitError, WaitOptions};
+use crate::layout::{display_width, pad_to_width, Table};
+use crate::program::{Console, MenuLayout, MenuOptions};
 use crate::protos::qni_api::*;
 
+use protobuf::{Message, ProtobufEnum};
+
 use std::mem;
 use std::slice;
 use std::sync::Arc;
+use std::time::Duration;
 
 use std::str;
 
@@ -25,6 +33,22 @@ pub unsafe extern "C" fn qni_console_exit(ctx: ConsoleArcCtx) {
     (*ctx).set_exit();
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn qni_console_exit_with(
+    ctx: ConsoleArcCtx,
+    code: i32,
+    reason: *const u8,
+    reason_len: usize,
+) {
+    let reason = str::from_utf8_unchecked(slice::from_raw_parts(reason, reason_len));
+    (*ctx).set_exit_with(code, reason);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_console_exit_code(ctx: ConsoleArcCtx) -> i32 {
+    (*ctx).exit_code()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn qni_console_need_exit(ctx: ConsoleArcCtx) -> i32 {
     if (*ctx).need_exit() {
@@ -58,6 +82,38 @@ pub unsafe extern "C" fn qni_print_line(ctx: ConsoleArcCtx, text: *const u8, len
     (*ctx).append_command(command);
 }
 
+/// Print markup, return -1 if markup is malformed
+#[no_mangle]
+pub unsafe extern "C" fn qni_print_markup(ctx: ConsoleArcCtx, text: *const u8, len: usize) -> i32 {
+    let text = str::from_utf8_unchecked(slice::from_raw_parts(text, len));
+
+    match Console::new((*ctx).clone()).print_markup(text) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Append encoded ProgramCommandArray, return `-1` if it can't be parsed
+#[no_mangle]
+pub unsafe extern "C" fn qni_append_commands(
+    ctx: ConsoleArcCtx,
+    buf: *const u8,
+    len: usize,
+) -> i32 {
+    match ProgramCommandArray::parse_from_bytes(slice::from_raw_parts(buf, len)) {
+        Ok(array) => {
+            (*ctx).append_commands(array.commands.into_iter());
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_set_coalesce_prints(ctx: ConsoleArcCtx, enabled: i32) {
+    (*ctx).set_coalesce_prints(enabled != 0);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn qni_draw_line(ctx: ConsoleArcCtx) {
     let mut command = ProgramCommand::new();
@@ -81,6 +137,7 @@ pub unsafe extern "C" fn qni_delete_line(ctx: ConsoleArcCtx, count: u32) {
     (*ctx).append_command(command);
 }
 
+/// `font_style` is bits of FontStyles, ITALIC 1, BOLD 2, UNDERLINE 4, STRIKETHROUGH 8
 #[no_mangle]
 pub unsafe extern "C" fn qni_set_font(
     ctx: ConsoleArcCtx,
@@ -109,7 +166,7 @@ pub unsafe extern "C" fn qni_set_text_align(ctx: ConsoleArcCtx, text_align: u32)
     let mut command = ProgramCommand::new();
     command
         .mut_UPDATE_SETTING()
-        .set_TEXT_ALIGN(mem::transmute(text_align as u8));
+        .set_TEXT_ALIGN(mem::transmute::<u8, TextAlign>(text_align as u8));
 
     (*ctx).append_command(command);
 }
@@ -138,6 +195,189 @@ pub unsafe extern "C" fn qni_set_highlight_color(ctx: ConsoleArcCtx, color: u32)
     (*ctx).append_command(command);
 }
 
+/// Parse hex color or palette name into `0xAARRGGBB`, return -1 if text is invalid
+#[no_mangle]
+pub unsafe extern "C" fn qni_parse_color(text: *const u8, len: usize, out: *mut u32) -> i32 {
+    let text = str::from_utf8_unchecked(slice::from_raw_parts(text, len));
+
+    match text.parse::<Color>() {
+        Ok(color) => {
+            *out = color.into();
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_set_text_color_str(
+    ctx: ConsoleArcCtx,
+    text: *const u8,
+    len: usize,
+) -> i32 {
+    set_color_str(ctx, text, len, ProgramCommand::text_color)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_set_back_color_str(
+    ctx: ConsoleArcCtx,
+    text: *const u8,
+    len: usize,
+) -> i32 {
+    set_color_str(ctx, text, len, ProgramCommand::back_color)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_set_highlight_color_str(
+    ctx: ConsoleArcCtx,
+    text: *const u8,
+    len: usize,
+) -> i32 {
+    set_color_str(ctx, text, len, ProgramCommand::highlight_color)
+}
+
+unsafe fn set_color_str(
+    ctx: ConsoleArcCtx,
+    text: *const u8,
+    len: usize,
+    command: fn(Color) -> ProgramCommand,
+) -> i32 {
+    let text = str::from_utf8_unchecked(slice::from_raw_parts(text, len));
+
+    match text.parse() {
+        Ok(color) => {
+            (*ctx).append_command(command(color));
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_push_settings(ctx: ConsoleArcCtx) {
+    (*ctx).push_settings();
+}
+
+/// Restore last pushed settings, return -1 if nothing is pushed
+#[no_mangle]
+pub unsafe extern "C" fn qni_pop_settings(ctx: ConsoleArcCtx) -> i32 {
+    if (*ctx).pop_settings() {
+        0
+    } else {
+        -1
+    }
+}
+
+/// Get current text color, return -1 if it is never set
+#[no_mangle]
+pub unsafe extern "C" fn qni_get_text_color(ctx: ConsoleArcCtx, color: *mut u32) -> i32 {
+    write_color((*ctx).text_color(), color)
+}
+
+/// Get current back color, return -1 if it is never set
+#[no_mangle]
+pub unsafe extern "C" fn qni_get_back_color(ctx: ConsoleArcCtx, color: *mut u32) -> i32 {
+    write_color((*ctx).back_color(), color)
+}
+
+/// Get current highlight color, return -1 if it is never set
+#[no_mangle]
+pub unsafe extern "C" fn qni_get_highlight_color(ctx: ConsoleArcCtx, color: *mut u32) -> i32 {
+    write_color((*ctx).highlight_color(), color)
+}
+
+unsafe fn write_color(value: Option<Color>, color: *mut u32) -> i32 {
+    match value {
+        Some(value) => {
+            *color = value.into();
+            0
+        }
+        None => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_get_text_align(ctx: ConsoleArcCtx) -> u32 {
+    (*ctx).text_align() as u32
+}
+
+/// Get current font, family buffer must be freed with `qni_buf_delete`
+///
+/// Return -1 and write nothing if font is never set
+#[no_mangle]
+pub unsafe extern "C" fn qni_get_font(
+    ctx: ConsoleArcCtx,
+    font_family: *mut *mut u8,
+    font_family_len: *mut usize,
+    font_family_cap: *mut usize,
+    font_size: *mut f32,
+    font_style: *mut u32,
+) -> i32 {
+    let mut font = match (*ctx).font() {
+        Some(font) => font,
+        None => return -1,
+    };
+    let mut family = mem::ManuallyDrop::new(font.take_font_family().into_bytes());
+
+    *font_family = family.as_mut_ptr();
+    *font_family_len = family.len();
+    *font_family_cap = family.capacity();
+    *font_size = font.font_size;
+    *font_style = font.font_style;
+
+    0
+}
+
+/// Count of cells text takes in monospace font
+#[no_mangle]
+pub unsafe extern "C" fn qni_display_width(text: *const u8, len: usize) -> usize {
+    display_width(str::from_utf8_unchecked(slice::from_raw_parts(text, len)))
+}
+
+/// Print text padded or truncated to display width
+#[no_mangle]
+pub unsafe extern "C" fn qni_print_padded(
+    ctx: ConsoleArcCtx,
+    text: *const u8,
+    len: usize,
+    width: usize,
+    text_align: u32,
+) {
+    let text = str::from_utf8_unchecked(slice::from_raw_parts(text, len));
+    let align = TextAlign::from_i32(text_align as i32).unwrap_or(TextAlign::LEFT);
+
+    (*ctx).append_command(ProgramCommand::print(pad_to_width(text, width, align)));
+}
+
+/// Print `rows * columns` cells in row order, first row is header if `header` is not 0
+#[no_mangle]
+pub unsafe extern "C" fn qni_print_table(
+    ctx: ConsoleArcCtx,
+    cells: *const *const u8,
+    cell_lens: *const usize,
+    rows: usize,
+    columns: usize,
+    header: i32,
+    border: i32,
+) {
+    let cells = slice::from_raw_parts(cells, rows * columns);
+    let cell_lens = slice::from_raw_parts(cell_lens, rows * columns);
+    let mut table = Table::new().border(border != 0);
+
+    for row in 0..rows {
+        let row_cells = (row * columns..(row + 1) * columns)
+            .map(|i| str::from_utf8_unchecked(slice::from_raw_parts(cells[i], cell_lens[i])));
+
+        table = if row == 0 && header != 0 {
+            table.header(row_cells)
+        } else {
+            table.row(row_cells)
+        };
+    }
+
+    (*ctx).append_commands(table.commands());
+}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum QniWaitResult {
@@ -145,9 +385,22 @@ pub enum QniWaitResult {
     Exited = 1,
     Timeout = 2,
     OutDated = 3,
+    Cancelled = 4,
     Internal = -1,
 }
 
+impl From<WaitError> for QniWaitResult {
+    fn from(err: WaitError) -> Self {
+        match err {
+            WaitError::Exited => QniWaitResult::Exited,
+            WaitError::Timeout => QniWaitResult::Timeout,
+            WaitError::OutDated => QniWaitResult::OutDated,
+            WaitError::Cancelled => QniWaitResult::Cancelled,
+            WaitError::EmptyMenu => QniWaitResult::Internal,
+        }
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn qni_wait(
     ctx: ConsoleArcCtx,
@@ -161,9 +414,73 @@ pub unsafe extern "C" fn qni_wait(
             *out = Box::into_raw(res);
             QniWaitResult::Ok
         }
-        Err(WaitError::Exited) => QniWaitResult::Exited,
-        Err(WaitError::Timeout) => QniWaitResult::Timeout,
-        Err(WaitError::OutDated) => QniWaitResult::OutDated,
+        Err(err) => err.into(),
+    }
+}
+
+/// Wait with relative timeout in milliseconds, `0` means no timeout
+///
+/// `default` can be null, otherwise it is returned when timeout and ownership is taken
+#[no_mangle]
+pub unsafe extern "C" fn qni_wait_with(
+    ctx: ConsoleArcCtx,
+    req: *mut ProgramRequest,
+    timeout_ms: u64,
+    default: *mut InputResponse,
+    out: *mut *mut ConsoleResponse,
+) -> QniWaitResult {
+    let req = Box::from_raw(req);
+    let mut options = WaitOptions::new();
+
+    if timeout_ms > 0 {
+        options = options.timeout(Duration::from_millis(timeout_ms));
+    }
+
+    if !default.is_null() {
+        options = options.default_response(*Box::from_raw(default));
+    }
+
+    match (*ctx).wait_console_with(*req, options) {
+        Ok(res) => {
+            *out = Box::into_raw(res);
+            QniWaitResult::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_cancel_token_new() -> *mut CancelToken {
+    Box::into_raw(Box::default())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_cancel_token_cancel(token: *const CancelToken) {
+    (*token).cancel();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn qni_cancel_token_delete(token: *mut CancelToken) {
+    let _ = Box::from_raw(token);
+}
+
+/// Wait until response or `token` is cancelled
+#[no_mangle]
+pub unsafe extern "C" fn qni_wait_cancellable(
+    ctx: ConsoleArcCtx,
+    req: *mut ProgramRequest,
+    token: *const CancelToken,
+    out: *mut *mut ConsoleResponse,
+) -> QniWaitResult {
+    let req = Box::from_raw(req);
+    let options = WaitOptions::new().cancel_token((*token).clone());
+
+    match (*ctx).wait_console_with(*req, options) {
+        Ok(res) => {
+            *out = Box::into_raw(res);
+            QniWaitResult::Ok
+        }
+        Err(err) => err.into(),
     }
 }
 
@@ -181,9 +498,7 @@ macro_rules! make_wait_fn {
                     $ok_block
                     QniWaitResult::Ok
                 }
-                Err(WaitError::Exited) => QniWaitResult::Exited,
-                Err(WaitError::Timeout) => QniWaitResult::Timeout,
-                Err(WaitError::OutDated) => QniWaitResult::OutDated,
+                Err(err) => err.into(),
             }
         }
     };
@@ -220,3 +535,43 @@ make_wait_fn!(
         *num = res.take_OK_INPUT().get_INT();
     }
 );
+
+/// Print buttons of labels and wait until one is selected, selected index is written to `index`
+///
+/// `layout` is 0 for vertical, 1 for horizontal and 2 for grid of `columns`
+#[no_mangle]
+pub unsafe extern "C" fn qni_menu(
+    ctx: ConsoleArcCtx,
+    labels: *const *const u8,
+    label_lens: *const usize,
+    count: usize,
+    layout: u32,
+    columns: usize,
+    numbered: i32,
+    index: *mut usize,
+) -> QniWaitResult {
+    if count == 0 {
+        return WaitError::EmptyMenu.into();
+    }
+
+    let labels: Vec<&str> = slice::from_raw_parts(labels, count)
+        .iter()
+        .zip(slice::from_raw_parts(label_lens, count))
+        .map(|(&label, &len)| str::from_utf8_unchecked(slice::from_raw_parts(label, len)))
+        .collect();
+    let layout = match layout {
+        0 => MenuLayout::Vertical,
+        1 => MenuLayout::Horizontal,
+        2 => MenuLayout::Grid(columns),
+        _ => return QniWaitResult::Internal,
+    };
+    let options = MenuOptions::new().layout(layout).numbered(numbered != 0);
+
+    match Console::new((*ctx).clone()).menu_index(&labels, options) {
+        Ok(selected) => {
+            *index = selected;
+            QniWaitResult::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
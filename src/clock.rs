@@ -0,0 +1,113 @@
+use chrono::prelude::*;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Time source of ConsoleContext
+///
+/// `now` is monotonic and used for every timeout,
+/// `wall_now` is only used to convert absolute time like `InputRequest.expire`.
+pub trait Clock: Send + Sync {
+    /// Monotonic time since clock is created
+    fn now(&self) -> Duration;
+
+    /// Wall clock time
+    fn wall_now(&self) -> DateTime<Utc>;
+
+    /// Block current thread while polling
+    fn sleep(&self, duration: Duration);
+
+    /// Convert wall clock time to monotonic time of this clock
+    fn to_monotonic(&self, time: DateTime<Utc>) -> Duration {
+        let now = self.now();
+
+        match (time - self.wall_now()).to_std() {
+            Ok(remain) => now.saturating_add(remain),
+            // already passed
+            Err(_) => now,
+        }
+    }
+}
+
+/// Clock based on `Instant` and system time
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemClock {
+    /// Create new SystemClock
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn wall_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Clock which only moves by `advance`
+///
+/// `sleep` doesn't wait given duration, it just yields shortly
+/// so waiting thread can see advanced time.
+pub struct ManualClock {
+    elapsed: Mutex<Duration>,
+    wall_start: DateTime<Utc>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Create new ManualClock starts at current wall clock time
+    pub fn new() -> Self {
+        Self::with_wall_time(Utc::now())
+    }
+
+    /// Create new ManualClock starts at given wall clock time
+    pub fn with_wall_time(wall_start: DateTime<Utc>) -> Self {
+        Self {
+            elapsed: Mutex::new(Duration::from_secs(0)),
+            wall_start,
+        }
+    }
+
+    /// Move clock forward
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+
+    fn wall_now(&self) -> DateTime<Utc> {
+        self.wall_start + chrono::Duration::from_std(self.now()).unwrap()
+    }
+
+    fn sleep(&self, _duration: Duration) {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
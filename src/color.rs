@@ -0,0 +1,186 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Color parse error
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// Text is neither hex color nor palette name
+    #[error("invalid color: {0}")]
+    Invalid(String),
+}
+
+/// Color stored as `0xAARRGGBB`
+///
+/// This is the byte order of `TEXT_COLOR`, `BACK_COLOR`, `HIGHLIGHT_COLOR` and
+/// `InputResponse.COLOR` on wire. Renderers ignore alpha.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color(pub u32);
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0x00, 0x00, 0x00);
+    pub const SILVER: Color = Color::rgb(0xC0, 0xC0, 0xC0);
+    pub const GRAY: Color = Color::rgb(0x80, 0x80, 0x80);
+    pub const WHITE: Color = Color::rgb(0xFF, 0xFF, 0xFF);
+    pub const MAROON: Color = Color::rgb(0x80, 0x00, 0x00);
+    pub const RED: Color = Color::rgb(0xFF, 0x00, 0x00);
+    pub const PURPLE: Color = Color::rgb(0x80, 0x00, 0x80);
+    pub const MAGENTA: Color = Color::rgb(0xFF, 0x00, 0xFF);
+    pub const GREEN: Color = Color::rgb(0x00, 0x80, 0x00);
+    pub const LIME: Color = Color::rgb(0x00, 0xFF, 0x00);
+    pub const OLIVE: Color = Color::rgb(0x80, 0x80, 0x00);
+    pub const YELLOW: Color = Color::rgb(0xFF, 0xFF, 0x00);
+    pub const NAVY: Color = Color::rgb(0x00, 0x00, 0x80);
+    pub const BLUE: Color = Color::rgb(0x00, 0x00, 0xFF);
+    pub const TEAL: Color = Color::rgb(0x00, 0x80, 0x80);
+    pub const CYAN: Color = Color::rgb(0x00, 0xFF, 0xFF);
+    pub const ORANGE: Color = Color::rgb(0xFF, 0xA5, 0x00);
+    pub const TRANSPARENT: Color = Color::argb(0x00, 0x00, 0x00, 0x00);
+
+    /// Named colors, names are CSS color keywords
+    pub const PALETTE: &'static [(&'static str, Color)] = &[
+        ("black", Color::BLACK),
+        ("silver", Color::SILVER),
+        ("gray", Color::GRAY),
+        ("white", Color::WHITE),
+        ("maroon", Color::MAROON),
+        ("red", Color::RED),
+        ("purple", Color::PURPLE),
+        ("fuchsia", Color::MAGENTA),
+        ("magenta", Color::MAGENTA),
+        ("green", Color::GREEN),
+        ("lime", Color::LIME),
+        ("olive", Color::OLIVE),
+        ("yellow", Color::YELLOW),
+        ("navy", Color::NAVY),
+        ("blue", Color::BLUE),
+        ("teal", Color::TEAL),
+        ("aqua", Color::CYAN),
+        ("cyan", Color::CYAN),
+        ("orange", Color::ORANGE),
+        ("transparent", Color::TRANSPARENT),
+    ];
+
+    /// Create opaque color
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::argb(0xFF, r, g, b)
+    }
+
+    /// Create color with alpha
+    pub const fn argb(a: u8, r: u8, g: u8, b: u8) -> Self {
+        Color((a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// Create color with alpha last
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::argb(a, r, g, b)
+    }
+
+    /// Get `0xAARRGGBB` value
+    pub const fn to_argb(self) -> u32 {
+        self.0
+    }
+
+    /// Alpha channel
+    pub const fn a(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    /// Red channel
+    pub const fn r(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// Green channel
+    pub const fn g(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    /// Blue channel
+    pub const fn b(self) -> u8 {
+        self.0 as u8
+    }
+
+    /// Get `(r, g, b, a)` tuple
+    pub const fn to_rgba(self) -> (u8, u8, u8, u8) {
+        (self.r(), self.g(), self.b(), self.a())
+    }
+
+    /// Same color with other alpha
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Color((self.0 & 0x00FF_FFFF) | (a as u32) << 24)
+    }
+
+    /// Find palette color by name, case insensitive
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::PALETTE
+            .iter()
+            .find(|(palette_name, _)| palette_name.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    /// Get first palette name of color
+    pub fn name(self) -> Option<&'static str> {
+        Self::PALETTE
+            .iter()
+            .find(|(_, color)| *color == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Format as `#rrggbb` ignoring alpha
+    pub fn to_css(self) -> String {
+        format!("#{:06x}", self.0 & 0x00FF_FFFF)
+    }
+}
+
+/// Parse `#rrggbb`, `#aarrggbb` or palette name
+///
+/// `#` is optional, colors without alpha are opaque
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let invalid = || ColorParseError::Invalid(text.into());
+
+        if let Some(color) = Color::from_name(text) {
+            return Ok(color);
+        }
+
+        let hex = text.strip_prefix('#').unwrap_or(text);
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let value = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+
+        match hex.len() {
+            6 => Ok(Color(0xFF00_0000 | value)),
+            8 => Ok(Color(value)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Format as `#rrggbb` if opaque, `#aarrggbb` otherwise
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.a() == 0xFF {
+            write!(f, "#{:06x}", self.0 & 0x00FF_FFFF)
+        } else {
+            write!(f, "#{:08x}", self.0)
+        }
+    }
+}
+
+impl From<u32> for Color {
+    fn from(argb: u32) -> Self {
+        Color(argb)
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
@@ -1,23 +1,60 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use protobuf::Message;
+
 use crate::console::ConsoleContext;
 use crate::protos::qni_api::*;
 
+/// Key of `ProgramMessage.RES`, field 11 with length delimited wire type
+const RES_KEY: u8 = 11 << 3 | 2;
+/// Key of `ProgramResponse.OK_GET_STATE`, field 12 with length delimited wire type
+const OK_GET_STATE_KEY: u8 = 12 << 3 | 2;
+
+fn encode_field(key: u8, bytes: &[u8]) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(bytes.len() + 11);
+    ret.push(key);
+
+    let mut len = bytes.len() as u64;
+
+    while len >= 0x80 {
+        ret.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+
+    ret.push(len as u8);
+    ret.extend_from_slice(bytes);
+    ret
+}
+
 /// Connect to ConsoleContext and handling events
 pub struct ConnectorContext {
     console_ctx: Arc<ConsoleContext>,
     last_req_tag: AtomicUsize,
     last_sended_req_tag: AtomicUsize,
+    last_default_tag: AtomicUsize,
+    last_withdrawn_tag: AtomicUsize,
+    exit_sended: AtomicBool,
 }
 
 impl ConnectorContext {
     /// Create new ConnectorContext
     pub fn new(console_ctx: Arc<ConsoleContext>) -> Self {
+        console_ctx.add_connector();
+
+        // default response and withdrawal before connect are not sent
+        let last_default_tag = console_ctx
+            .get_default_response()
+            .map_or(0, |res| res.get_tag() as usize + 1);
+        let last_withdrawn_tag = console_ctx.get_withdrawn_tag();
+
         Self {
             console_ctx,
             last_req_tag: AtomicUsize::new(0),
             last_sended_req_tag: AtomicUsize::new(0),
+            last_default_tag: AtomicUsize::new(last_default_tag),
+            last_withdrawn_tag: AtomicUsize::new(last_withdrawn_tag),
+            exit_sended: AtomicBool::new(false),
         }
     }
 
@@ -54,6 +91,28 @@ impl ConnectorContext {
         }
     }
 
+    /// Same as `on_recv_message` but return encoded ProgramMessage
+    ///
+    /// OK_GET_STATE is built by splicing cached bytes of commands
+    /// instead of cloning and encoding them again.
+    pub fn on_recv_message_encoded(&self, msg: ConsoleMessage) -> Option<Vec<u8>> {
+        if let Some(ConsoleRequest_oneof_data::GET_STATE(from)) = msg.get_REQ().data {
+            let ctx = &self.console_ctx;
+            let from = from as usize;
+
+            if !(ctx.need_exit() && from >= ctx.get_command_count()) {
+                let array = ctx.command_snapshot(from).encode_array();
+                let res = encode_field(OK_GET_STATE_KEY, &array);
+                return Some(encode_field(RES_KEY, &res));
+            }
+        }
+
+        self.on_recv_message(msg).map(|res| {
+            res.write_to_bytes()
+                .expect("ProgramMessage has no required field")
+        })
+    }
+
     /// Handling ConsoleMessage receive and return ProgramMessage to response
     pub fn on_recv_message(&self, mut msg: ConsoleMessage) -> Option<ProgramMessage> {
         if msg.has_REQ() {
@@ -71,28 +130,87 @@ impl ConnectorContext {
         }
     }
 
+    /// Get default response which is not sent yet
+    fn take_default_response(&self) -> Option<ConsoleResponse> {
+        let res = self.console_ctx.get_default_response()?;
+        let tag = res.get_tag() as usize + 1;
+
+        if tag > self.last_default_tag.load(Ordering::Relaxed) {
+            self.last_default_tag.store(tag, Ordering::Relaxed);
+            Some(res)
+        } else {
+            None
+        }
+    }
+
+    /// Get withdrawn request tag which is not sent yet
+    fn take_withdrawn_tag(&self) -> Option<u32> {
+        let tag = self.console_ctx.get_withdrawn_tag();
+
+        if tag > self.last_withdrawn_tag.load(Ordering::Relaxed) {
+            self.last_withdrawn_tag.store(tag, Ordering::Relaxed);
+            Some(tag as u32 - 1)
+        } else {
+            None
+        }
+    }
+
     /// Return ProgramMessage when need to send message to FrontEnd
+    ///
+    /// `WITHDRAW_REQ` and `DEFAULT_RES` are sent before new request,
+    /// `EXIT` is sent once after other messages when console exited
     pub fn try_get_msg(&self) -> Option<ProgramMessage> {
-        if self.console_ctx.get_cur_input_tag() > self.last_req_tag.load(Ordering::Relaxed) {
-            self.console_ctx.try_get_req().map(|req| {
+        let mut msg = ProgramMessage::new();
+
+        if let Some(tag) = self.take_withdrawn_tag() {
+            self.skip_request(tag);
+            msg.set_WITHDRAW_REQ(tag);
+            return Some(msg);
+        }
+
+        if let Some(res) = self.take_default_response() {
+            self.skip_request(res.get_tag());
+            msg.set_DEFAULT_RES(res);
+            return Some(msg);
+        }
+
+        let last_tag = self.last_req_tag.load(Ordering::Relaxed);
+
+        // request can be withdrawn before it is sent, then fall through
+        if !self.need_exit() && self.console_ctx.get_cur_input_tag() > last_tag {
+            if let Some(req) = self.console_ctx.try_get_req() {
                 self.last_req_tag
                     .store(req.tag as usize + 1, Ordering::Relaxed);
-                let mut msg = ProgramMessage::new();
                 msg.set_REQ(req);
-                msg
-            })
-        } else {
-            let sended_tag = self.last_sended_req_tag.load(Ordering::Relaxed);
-            let last_tag = self.last_req_tag.load(Ordering::Relaxed);
-
-            if sended_tag < last_tag {
-                let mut msg = ProgramMessage::new();
-                msg.set_ACCEPT_RES(last_tag as u32 - 1);
-                self.last_sended_req_tag.store(last_tag, Ordering::Relaxed);
-                Some(msg)
-            } else {
-                None
+                return Some(msg);
             }
         }
+
+        let sended_tag = self.last_sended_req_tag.load(Ordering::Relaxed);
+
+        if sended_tag < last_tag {
+            msg.set_ACCEPT_RES(last_tag as u32 - 1);
+            self.last_sended_req_tag.store(last_tag, Ordering::Relaxed);
+            Some(msg)
+        } else if self.need_exit() && !self.exit_sended.swap(true, Ordering::Relaxed) {
+            msg.set_EXIT(self.console_ctx.exit_status().unwrap_or_default());
+            Some(msg)
+        } else {
+            None
+        }
+    }
+
+    /// Request which is already finished doesn't need `REQ` nor `ACCEPT_RES`
+    fn skip_request(&self, tag: u32) {
+        self.last_req_tag
+            .fetch_max(tag as usize + 1, Ordering::Relaxed);
+        self.last_sended_req_tag
+            .fetch_max(tag as usize + 1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ConnectorContext {
+    fn drop(&mut self) {
+        self.console_ctx.remove_connector();
     }
 }
@@ -1,12 +1,20 @@
 use atomic_option::AtomicOption;
 use chrono::prelude::*;
 use protobuf::well_known_types::Timestamp;
+use std::ops::Range;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::RwLock;
-use std::thread;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
+use crate::clock::{Clock, SystemClock};
+use crate::color::Color;
+use crate::event::{ConsoleEvent, EventHub, ListenerId};
+use crate::log::{CommandLog, CommandSnapshot};
 use crate::protos::qni_api::*;
+use crate::record::{RecordEvent, Recorder};
+use crate::screen::ConsoleSettings;
+use crate::session::ConsoleSnapshot;
 
 /// Console wait error
 #[derive(Debug, thiserror::Error)]
@@ -20,29 +28,188 @@ pub enum WaitError {
     /// Other request is enter before get response
     #[error("request outdated")]
     OutDated,
+    /// CancelToken is cancelled before get response
+    #[error("wait cancelled")]
+    Cancelled,
+    /// Menu has no item to select
+    #[error("menu has no item")]
+    EmptyMenu,
+}
+
+/// Token to cancel pending wait from other thread
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create new CancelToken
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel wait which uses this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Token is cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Options of `wait_console_with`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WaitOptions {
+    /// Max time to wait from request, earlier one is used if request has `expire` too
+    pub timeout: Option<Duration>,
+    /// Response returned when request is expired instead of `WaitError::Timeout`
+    pub default: Option<InputResponse>,
+    /// Token to withdraw request
+    pub cancel: Option<CancelToken>,
+}
+
+impl WaitOptions {
+    /// Create new WaitOptions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set relative timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set default response
+    pub fn default_response(mut self, default: InputResponse) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Set cancel token
+    pub fn cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+/// Restore settings saved by `ConsoleContext::scoped_settings` when dropped
+#[must_use = "settings are restored when guard is dropped"]
+pub struct SettingsGuard<'a> {
+    ctx: &'a ConsoleContext,
+}
+
+impl Drop for SettingsGuard<'_> {
+    fn drop(&mut self) {
+        self.ctx.pop_settings();
+    }
 }
 
 /// Present ConsoleContext
 pub struct ConsoleContext {
-    commands: RwLock<Vec<ProgramCommand>>,
+    commands: CommandLog,
+    coalesce_prints: AtomicBool,
+    /// PRINT not appended yet to merge following PRINTs
+    pending_print: Mutex<Option<ProgramCommand>>,
+    /// Settings after every appended command
+    settings: Mutex<ConsoleSettings>,
+    /// Settings saved by `push_settings`
+    settings_stack: Mutex<Vec<ConsoleSettings>>,
     exit_flag: AtomicBool,
+    exit_status: RwLock<Option<ProgramExit>>,
     request_tag: AtomicUsize,
     request: RwLock<Option<ProgramRequest>>,
     response: AtomicOption<ConsoleResponse>,
+    default_response: RwLock<Option<ConsoleResponse>>,
+    withdrawn_tag: AtomicUsize,
+    connector_count: AtomicUsize,
+    events: EventHub,
+    recorder: RwLock<Option<ListenerId>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for ConsoleContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ConsoleContext {
     /// Create new ConsoleContext
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock::new()))
+    }
+
+    /// Create new ConsoleContext with clock
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
-            commands: Default::default(),
+            commands: CommandLog::new(),
+            coalesce_prints: AtomicBool::new(false),
+            pending_print: Mutex::new(None),
+            settings: Mutex::new(ConsoleSettings::default()),
+            settings_stack: Mutex::new(Vec::new()),
             exit_flag: AtomicBool::new(false),
+            exit_status: RwLock::new(None),
             request_tag: AtomicUsize::new(0),
             response: AtomicOption::empty(),
+            default_response: RwLock::new(None),
+            withdrawn_tag: AtomicUsize::new(0),
+            connector_count: AtomicUsize::new(0),
             request: RwLock::new(None),
+            events: EventHub::default(),
+            recorder: RwLock::new(None),
+            clock,
         }
     }
 
+    /// Create ConsoleContext from snapshot
+    ///
+    /// Command indices and request tag are kept, so frontends can continue GET_STATE
+    pub fn from_snapshot(snapshot: ConsoleSnapshot) -> Self {
+        Self::from_snapshot_with_clock(snapshot, Arc::new(SystemClock::new()))
+    }
+
+    /// Create ConsoleContext from snapshot with clock
+    pub fn from_snapshot_with_clock(snapshot: ConsoleSnapshot, clock: Arc<dyn Clock>) -> Self {
+        let mut ctx = Self::with_clock(clock);
+        let settings = ctx.settings.get_mut().unwrap();
+
+        for command in &snapshot.commands {
+            if command.has_UPDATE_SETTING() {
+                settings.apply(command.get_UPDATE_SETTING());
+            }
+        }
+
+        ctx.commands = CommandLog::from_commands(snapshot.commands);
+        *ctx.exit_flag.get_mut() = snapshot.exit;
+        *ctx.exit_status.get_mut().unwrap() = snapshot.exit_status;
+        *ctx.request_tag.get_mut() = snapshot.request_tag;
+        *ctx.request.get_mut().unwrap() = snapshot.request;
+        ctx
+    }
+
+    /// Take snapshot of current state
+    pub fn snapshot(&self) -> ConsoleSnapshot {
+        ConsoleSnapshot {
+            commands: self.export_command(0),
+            request_tag: self.get_cur_input_tag(),
+            request: self.try_get_req(),
+            exit: self.need_exit(),
+            exit_status: self.exit_status(),
+        }
+    }
+
+    /// Clock used for timeouts
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
     /// Console need exit
     pub fn need_exit(&self) -> bool {
         self.exit_flag.load(Ordering::Relaxed)
@@ -50,27 +217,261 @@ impl ConsoleContext {
 
     /// Set console exit flag
     pub fn set_exit(&self) {
-        self.exit_flag.store(true, Ordering::Relaxed)
+        if !self.exit_flag.swap(true, Ordering::Relaxed) {
+            self.events.emit(|| ConsoleEvent::Exit(self.exit_status()));
+        }
+    }
+
+    /// Set console exit flag with exit code and reason
+    ///
+    /// Only first status is kept when called multiple times
+    pub fn set_exit_with(&self, code: i32, reason: impl Into<String>) {
+        {
+            let mut status = self.exit_status.write().unwrap();
+
+            if status.is_none() {
+                let mut exit = ProgramExit::new();
+                exit.set_code(code);
+                exit.set_reason(reason.into());
+                *status = Some(exit);
+            }
+        }
+
+        self.set_exit();
+    }
+
+    /// Get exit status set by `set_exit_with`
+    pub fn exit_status(&self) -> Option<ProgramExit> {
+        self.exit_status.read().unwrap().clone()
+    }
+
+    /// Get exit code, `0` if exit code isn't set
+    pub fn exit_code(&self) -> i32 {
+        self.exit_status
+            .read()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |status| status.get_code())
+    }
+
+    /// Get count of alive ConnectorContext of this console
+    pub fn connector_count(&self) -> usize {
+        self.connector_count.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_connector(&self) {
+        self.connector_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn remove_connector(&self) {
+        self.connector_count.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Subscribe events, channel is removed when receiver is dropped
+    pub fn subscribe(&self) -> Receiver<ConsoleEvent> {
+        self.events.subscribe()
+    }
+
+    /// Add listener called on thread which makes event
+    ///
+    /// Listener can add or remove listeners, removed listener may still get current event
+    pub fn add_listener(
+        &self,
+        listener: impl Fn(&ConsoleEvent) + Send + Sync + 'static,
+    ) -> ListenerId {
+        self.events.add_listener(Box::new(listener))
+    }
+
+    /// Remove listener, return `false` if it is already removed
+    pub fn remove_listener(&self, id: ListenerId) -> bool {
+        self.events.remove_listener(id)
+    }
+
+    /// Set recorder which records commands, requests and responses
+    pub fn set_recorder(&self, recorder: Option<Arc<Recorder>>) {
+        let mut listener = self.recorder.write().unwrap();
+
+        if let Some(id) = listener.take() {
+            self.remove_listener(id);
+        }
+
+        *listener = recorder.map(|recorder| {
+            self.add_listener(move |event| {
+                let event = match event {
+                    ConsoleEvent::Command { command, .. } => RecordEvent::Command(command.clone()),
+                    ConsoleEvent::Request(req) => RecordEvent::Request(req.clone()),
+                    ConsoleEvent::Response(res) => RecordEvent::Response(res.clone()),
+                    _ => return,
+                };
+
+                recorder.record(event);
+            })
+        });
     }
 
     /// Append console command
     pub fn append_command(&self, command: ProgramCommand) {
-        self.commands.write().unwrap().push(command);
+        self.append_commands(Some(command));
     }
 
     pub fn append_command_mut(&mut self, command: ProgramCommand) {
-        self.commands.get_mut().unwrap().push(command);
+        self.append_command(command);
+    }
+
+    /// Append console commands at once
+    pub fn append_commands(&self, commands: impl IntoIterator<Item = ProgramCommand>) {
+        let range = {
+            let mut settings = self.settings.lock().unwrap();
+            let commands = commands.into_iter().inspect(|command| {
+                if command.has_UPDATE_SETTING() {
+                    settings.apply(command.get_UPDATE_SETTING());
+                }
+            });
+
+            // flag is read under pending lock so PRINT isn't left pending after it is disabled
+            let mut pending = self.pending_print.lock().unwrap();
+
+            if self.coalesce_prints.load(Ordering::Relaxed) {
+                let mut ready = Vec::new();
+
+                for command in commands {
+                    if is_plain_print(&command) {
+                        match pending.as_mut() {
+                            Some(print) => print
+                                .mut_PRINT()
+                                .mut_PRINT()
+                                .push_str(command.get_PRINT().get_PRINT()),
+                            None => *pending = Some(command),
+                        }
+                    } else {
+                        ready.extend(pending.take());
+                        ready.push(command);
+                    }
+                }
+
+                self.commands.append_all(ready)
+            } else {
+                self.commands.append_all(commands)
+            }
+        };
+
+        self.emit_commands(range);
+    }
+
+    /// Merge consecutive PRINTs into one until commands are read
+    ///
+    /// Pending PRINT is appended when other command is appended or
+    /// commands are read by `command_snapshot`, `export_command` or `get_command_count`,
+    /// so frontends never see command which is changed later.
+    pub fn set_coalesce_prints(&self, enabled: bool) {
+        let range = {
+            let mut pending = self.pending_print.lock().unwrap();
+            self.coalesce_prints.store(enabled, Ordering::Relaxed);
+            self.commands.append_all(pending.take())
+        };
+
+        self.emit_commands(range);
+    }
+
+    /// Current settings applied by appended commands
+    pub fn settings(&self) -> ConsoleSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Current text color, `None` if it is never set
+    pub fn text_color(&self) -> Option<Color> {
+        self.settings().text_color.map(Color)
+    }
+
+    /// Current back color, `None` if it is never set
+    pub fn back_color(&self) -> Option<Color> {
+        self.settings().back_color.map(Color)
+    }
+
+    /// Current highlight color, `None` if it is never set
+    pub fn highlight_color(&self) -> Option<Color> {
+        self.settings().highlight_color.map(Color)
+    }
+
+    /// Current font, `None` if it is never set
+    pub fn font(&self) -> Option<Font> {
+        self.settings().font
+    }
+
+    /// Current text align
+    pub fn text_align(&self) -> TextAlign {
+        self.settings().text_align
+    }
+
+    /// Save current settings
+    pub fn push_settings(&self) {
+        let settings = self.settings();
+        self.settings_stack.lock().unwrap().push(settings);
+    }
+
+    /// Restore last saved settings by appending UPDATE_SETTING commands
+    ///
+    /// Return `false` if there is no saved settings
+    pub fn pop_settings(&self) -> bool {
+        let mut stack = self.settings_stack.lock().unwrap();
+
+        match stack.pop() {
+            Some(saved) => {
+                self.append_commands(self.settings().update_commands(&saved));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Save current settings and restore them when guard is dropped
+    pub fn scoped_settings(&self) -> SettingsGuard<'_> {
+        self.push_settings();
+        SettingsGuard { ctx: self }
+    }
+
+    fn flush_pending_print(&self) {
+        if !self.coalesce_prints.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let range = {
+            let mut pending = self.pending_print.lock().unwrap();
+
+            match pending.take() {
+                Some(print) => self.commands.append_all(Some(print)),
+                None => return,
+            }
+        };
+
+        self.emit_commands(range);
+    }
+
+    fn emit_commands(&self, range: Range<usize>) {
+        for index in range {
+            self.events.emit(|| ConsoleEvent::Command {
+                index,
+                command: self.commands.get(index).unwrap(),
+            });
+        }
+    }
+
+    /// Take snapshot of commands from index, commands are shared with log and not copied
+    pub fn command_snapshot(&self, from: usize) -> CommandSnapshot {
+        self.flush_pending_print();
+        self.commands.snapshot(from)
     }
 
     /// Export command to Vec
     pub fn export_command(&self, from: usize) -> Vec<ProgramCommand> {
-        Vec::from(&self.commands.read().unwrap()[from..])
+        self.command_snapshot(from).to_vec()
     }
 
     /// Get current command count
     #[inline]
     pub fn get_command_count(&self) -> usize {
-        self.commands.read().unwrap().len()
+        self.flush_pending_print();
+        self.commands.len()
     }
 
     /// Get next input tag
@@ -88,14 +489,18 @@ impl ConsoleContext {
     /// Receive ConsoleResponse message
     pub fn on_recv_response(&self, res: ConsoleResponse) {
         if !self.is_outdated_tag(res.get_tag() as usize) {
+            self.events.emit(|| ConsoleEvent::Response(res.clone()));
             self.response.swap(Box::new(res), Ordering::Release);
         }
     }
 
     /// Set current request
     fn set_req(&self, mut req: ProgramRequest) -> usize {
+        self.flush_pending_print();
+
         let tag = self.get_next_input_tag();
         req.set_tag(tag as u32);
+        self.events.emit(|| ConsoleEvent::Request(req.clone()));
         *self.request.write().unwrap() = Some(req);
         tag
     }
@@ -105,6 +510,16 @@ impl ConsoleContext {
         self.request.read().unwrap().as_ref().map(Clone::clone)
     }
 
+    /// Get last default response applied by timeout
+    pub fn get_default_response(&self) -> Option<ConsoleResponse> {
+        self.default_response.read().unwrap().clone()
+    }
+
+    /// Get last withdrawn request tag + 1, `0` means no request is withdrawn
+    pub fn get_withdrawn_tag(&self) -> usize {
+        self.withdrawn_tag.load(Ordering::Relaxed)
+    }
+
     /// Check if tag is outdated
     pub fn is_outdated_tag(&self, tag: usize) -> bool {
         tag + 1 < self.get_cur_input_tag()
@@ -116,29 +531,89 @@ impl ConsoleContext {
     ///
     /// If Console exited, tag is outdated, or request is expired, then error is returned
     pub fn wait_console(&self, req: ProgramRequest) -> Result<Box<ConsoleResponse>, WaitError> {
-        let expire = if req.get_INPUT().has_expire() {
+        self.wait_console_with(req, WaitOptions::new())
+    }
+
+    /// Wait ConsoleResponse with options
+    ///
+    /// Relative timeout is written to `expire` of request so frontends can show it.
+    /// `expire` is converted to monotonic deadline of clock when request is set,
+    /// so later wall clock jump doesn't affect it.
+    ///
+    /// When request is expired and default response is set, it is returned as response
+    /// and frontends get it as `DEFAULT_RES` message.
+    ///
+    /// When cancel token is cancelled, request is withdrawn
+    /// and frontends get its tag as `WITHDRAW_REQ` message.
+    ///
+    /// # Errors
+    ///
+    /// If Console exited, tag is outdated, request is expired without default or cancelled,
+    /// then error is returned
+    pub fn wait_console_with(
+        &self,
+        mut req: ProgramRequest,
+        options: WaitOptions,
+    ) -> Result<Box<ConsoleResponse>, WaitError> {
+        let mut deadline = if req.get_INPUT().has_expire() {
             let expire: &Timestamp = req.get_INPUT().get_expire();
-            Some(Utc.timestamp(expire.seconds, expire.nanos as u32))
+            Utc.timestamp_opt(expire.seconds, expire.nanos as u32)
+                .single()
+                .map(|expire| self.clock.to_monotonic(expire))
         } else {
             None
         };
 
+        // timeout which can't be represented never expires
+        let timeout = options.timeout.and_then(|timeout| {
+            let timeout_deadline = self.clock.now().checked_add(timeout)?;
+            let expire = self
+                .clock
+                .wall_now()
+                .checked_add_signed(chrono::Duration::from_std(timeout).ok()?)?;
+            Some((timeout_deadline, expire))
+        });
+
+        if let Some((timeout_deadline, expire)) = timeout {
+            if deadline.is_none_or(|deadline| timeout_deadline < deadline) {
+                let input = req.mut_INPUT();
+                input.mut_expire().set_seconds(expire.timestamp());
+                input
+                    .mut_expire()
+                    .set_nanos(expire.timestamp_subsec_nanos() as i32);
+                deadline = Some(timeout_deadline);
+            }
+        }
+
         let tag = self.set_req(req);
 
-        let ret = loop {
+        loop {
             if self.need_exit() {
                 break Err(WaitError::Exited);
             }
 
+            if options
+                .cancel
+                .as_ref()
+                .is_some_and(CancelToken::is_cancelled)
+            {
+                self.withdraw(tag);
+                break Err(WaitError::Cancelled);
+            }
+
             let response = self.response.take(Ordering::Acquire);
 
-            if let Some(response) = response {
+            // response of withdrawn or expired request can arrive late
+            if let Some(response) = response.filter(|res| res.get_tag() as usize == tag) {
                 break Ok(response);
             }
 
-            if let Some(expire) = expire {
-                if Utc::now() >= expire {
-                    break Err(WaitError::Timeout);
+            if let Some(deadline) = deadline {
+                if self.clock.now() >= deadline {
+                    break match options.default {
+                        Some(default) => Ok(Box::new(self.apply_default(tag, default))),
+                        None => Err(WaitError::Timeout),
+                    };
                 }
             }
 
@@ -146,9 +621,43 @@ impl ConsoleContext {
                 break Err(WaitError::Timeout);
             }
 
-            thread::sleep(Duration::from_millis(100));
-        };
+            self.clock.sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Clear current request if it has `tag`
+    fn clear_request(&self, tag: usize) {
+        let mut request = self.request.write().unwrap();
+
+        if request
+            .as_ref()
+            .is_some_and(|req| req.get_tag() as usize == tag)
+        {
+            *request = None;
+        }
+    }
 
-        ret
+    fn withdraw(&self, tag: usize) {
+        self.clear_request(tag);
+        self.withdrawn_tag.store(tag + 1, Ordering::Relaxed);
+        self.events.emit(|| ConsoleEvent::Withdrawn(tag as u32));
     }
+
+    fn apply_default(&self, tag: usize, default: InputResponse) -> ConsoleResponse {
+        self.clear_request(tag);
+
+        let mut res = ConsoleResponse::new();
+        res.set_tag(tag as u32);
+        res.set_OK_INPUT(default);
+        self.events.emit(|| ConsoleEvent::Response(res.clone()));
+        *self.default_response.write().unwrap() = Some(res.clone());
+        res
+    }
+}
+
+fn is_plain_print(command: &ProgramCommand) -> bool {
+    matches!(
+        command.get_PRINT().data,
+        Some(ConsolePrintData_oneof_data::PRINT(_))
+    )
 }
@@ -0,0 +1,142 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use chrono::prelude::*;
+use protobuf::well_known_types::Timestamp;
+
+use crate::color::Color;
+use crate::protos::qni_api::*;
+
+/// InputResponse conversion error
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ConvertError {
+    /// Response has other variant
+    #[error("expected {expected} response but found {found}")]
+    Mismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// Value can't be represented in target type
+    #[error("{0} response is out of range")]
+    OutOfRange(&'static str),
+}
+
+/// Get name of response variant
+pub fn response_kind(res: &InputResponse) -> &'static str {
+    match res.data {
+        Some(InputResponse_oneof_data::EMPTY(_)) => "empty",
+        Some(InputResponse_oneof_data::BOOLEAN(_)) => "boolean",
+        Some(InputResponse_oneof_data::STR(_)) => "string",
+        Some(InputResponse_oneof_data::INT(_)) => "integer",
+        Some(InputResponse_oneof_data::FLOAT(_)) => "float",
+        Some(InputResponse_oneof_data::DATE(_)) => "date",
+        Some(InputResponse_oneof_data::DATETIME(_)) => "datetime",
+        Some(InputResponse_oneof_data::TIME(_)) => "time",
+        Some(InputResponse_oneof_data::COLOR(_)) => "color",
+        None => "none",
+    }
+}
+
+fn mismatch(expected: &'static str, res: &InputResponse) -> ConvertError {
+    ConvertError::Mismatch {
+        expected,
+        found: response_kind(res),
+    }
+}
+
+fn from_timestamp(
+    kind: &'static str,
+    timestamp: &Timestamp,
+) -> Result<DateTime<Utc>, ConvertError> {
+    u32::try_from(timestamp.get_nanos())
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(timestamp.get_seconds(), nanos))
+        .ok_or(ConvertError::OutOfRange(kind))
+}
+
+macro_rules! impl_try_from {
+    ($ty:ty, $kind:literal, $variant:ident) => {
+        impl TryFrom<InputResponse> for $ty {
+            type Error = ConvertError;
+
+            fn try_from(res: InputResponse) -> Result<Self, Self::Error> {
+                match res.data {
+                    Some(InputResponse_oneof_data::$variant(value)) => Ok(value.into()),
+                    _ => Err(mismatch($kind, &res)),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from!(bool, "boolean", BOOLEAN);
+impl_try_from!(i32, "integer", INT);
+impl_try_from!(f32, "float", FLOAT);
+impl_try_from!(String, "string", STR);
+impl_try_from!(Color, "color", COLOR);
+
+impl TryFrom<InputResponse> for NaiveDate {
+    type Error = ConvertError;
+
+    fn try_from(res: InputResponse) -> Result<Self, Self::Error> {
+        match res.data {
+            Some(InputResponse_oneof_data::DATE(ref date)) => {
+                Ok(from_timestamp("date", date)?.date_naive())
+            }
+            _ => Err(mismatch("date", &res)),
+        }
+    }
+}
+
+impl TryFrom<InputResponse> for DateTime<Utc> {
+    type Error = ConvertError;
+
+    fn try_from(res: InputResponse) -> Result<Self, Self::Error> {
+        match res.data {
+            Some(InputResponse_oneof_data::DATETIME(ref datetime)) => {
+                from_timestamp("datetime", datetime)
+            }
+            _ => Err(mismatch("datetime", &res)),
+        }
+    }
+}
+
+/// TIME response is duration since midnight
+impl TryFrom<InputResponse> for Duration {
+    type Error = ConvertError;
+
+    fn try_from(res: InputResponse) -> Result<Self, Self::Error> {
+        match res.data {
+            Some(InputResponse_oneof_data::TIME(ref time)) => {
+                match (
+                    u64::try_from(time.get_seconds()),
+                    u32::try_from(time.get_nanos()),
+                ) {
+                    (Ok(secs), Ok(nanos)) if nanos < 1_000_000_000 => {
+                        Ok(Duration::new(secs, nanos))
+                    }
+                    _ => Err(ConvertError::OutOfRange("time")),
+                }
+            }
+            _ => Err(mismatch("time", &res)),
+        }
+    }
+}
+
+impl From<NaiveDate> for InputResponse {
+    fn from(date: NaiveDate) -> Self {
+        InputResponse::date(date)
+    }
+}
+
+impl From<DateTime<Utc>> for InputResponse {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        InputResponse::datetime(datetime)
+    }
+}
+
+impl From<Duration> for InputResponse {
+    fn from(since_midnight: Duration) -> Self {
+        InputResponse::time(since_midnight)
+    }
+}
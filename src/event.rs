@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::protos::qni_api::*;
+
+/// Event of ConsoleContext
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsoleEvent {
+    /// Command is appended at index
+    Command {
+        index: usize,
+        command: ProgramCommand,
+    },
+    /// Request is set, tag is assigned
+    Request(ProgramRequest),
+    /// Response is delivered to program, including default response applied by timeout
+    Response(ConsoleResponse),
+    /// Request of tag is withdrawn by CancelToken
+    Withdrawn(u32),
+    /// Console exited, status is set if `set_exit_with` is used
+    Exit(Option<ProgramExit>),
+}
+
+/// ID of listener to remove it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(usize);
+
+type Listener = Box<dyn Fn(&ConsoleEvent) + Send + Sync>;
+
+enum Subscriber {
+    Listener(ListenerId, Arc<dyn Fn(&ConsoleEvent) + Send + Sync>),
+    Channel(Sender<ConsoleEvent>),
+}
+
+/// Deliver events to listeners and channels
+#[derive(Default)]
+pub(crate) struct EventHub {
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_id: AtomicUsize,
+}
+
+impl EventHub {
+    pub fn subscribe(&self) -> Receiver<ConsoleEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Subscriber::Channel(tx));
+        rx
+    }
+
+    pub fn add_listener(&self, listener: Listener) -> ListenerId {
+        let id = ListenerId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Subscriber::Listener(id, listener.into()));
+        id
+    }
+
+    pub fn remove_listener(&self, id: ListenerId) -> bool {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let len = subscribers.len();
+
+        subscribers.retain(|subscriber| {
+            !matches!(subscriber, Subscriber::Listener(listener_id, _) if *listener_id == id)
+        });
+
+        subscribers.len() != len
+    }
+
+    /// Event is only made when there is subscriber
+    ///
+    /// Listeners are called after lock is released, so they can add or remove listeners
+    pub fn emit(&self, event: impl FnOnce() -> ConsoleEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let event = event();
+        let mut listeners = Vec::new();
+
+        // drop channels which receiver is dropped
+        subscribers.retain(|subscriber| match subscriber {
+            Subscriber::Listener(_, listener) => {
+                listeners.push(listener.clone());
+                true
+            }
+            Subscriber::Channel(tx) => tx.send(event.clone()).is_ok(),
+        });
+
+        drop(subscribers);
+
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+}
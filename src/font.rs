@@ -0,0 +1,39 @@
+use crate::protos::qni_api::*;
+
+bitflags::bitflags! {
+    /// Combinable font styles stored in `Font.font_style`
+    ///
+    /// ITALIC and BOLD have same value as FontStyle, so single style is encoded same
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct FontStyles: u32 {
+        const ITALIC = 1;
+        const BOLD = 2;
+        const UNDERLINE = 4;
+        const STRIKETHROUGH = 8;
+    }
+}
+
+impl From<FontStyle> for FontStyles {
+    fn from(style: FontStyle) -> Self {
+        FontStyles::from_bits_retain(style as u32)
+    }
+}
+
+impl From<u32> for FontStyles {
+    fn from(bits: u32) -> Self {
+        FontStyles::from_bits_retain(bits)
+    }
+}
+
+impl From<FontStyles> for u32 {
+    fn from(styles: FontStyles) -> Self {
+        styles.bits()
+    }
+}
+
+impl Font {
+    /// Get styles of font
+    pub fn styles(&self) -> FontStyles {
+        self.font_style.into()
+    }
+}
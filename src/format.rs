@@ -0,0 +1,109 @@
+//! Shared file format of session snapshots and recordings
+//!
+//! Files start with 4 bytes magic and little endian u32 version,
+//! messages are prefixed with little endian u32 length.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use protobuf::Message;
+
+/// Session or recording save or load error
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("protobuf error: {0}")]
+    Protobuf(#[from] protobuf::ProtobufError),
+    /// Data doesn't start with expected magic
+    #[error("invalid header")]
+    InvalidHeader,
+    /// Data is saved by newer version
+    #[error("unsupported version {0}")]
+    UnsupportedVersion(u32),
+    /// Unknown recording entry kind
+    #[error("invalid entry kind {0}")]
+    InvalidEntry(u8),
+}
+
+pub(crate) fn write_header(
+    writer: &mut impl Write,
+    magic: &[u8; 4],
+    version: u32,
+) -> Result<(), FormatError> {
+    writer.write_all(magic)?;
+    writer.write_all(&version.to_le_bytes())?;
+    Ok(())
+}
+
+/// Read header and return version
+pub(crate) fn read_header(
+    reader: &mut impl Read,
+    magic: &[u8; 4],
+    max_version: u32,
+) -> Result<u32, FormatError> {
+    let mut buf = [0; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|_| FormatError::InvalidHeader)?;
+
+    if &buf != magic {
+        return Err(FormatError::InvalidHeader);
+    }
+
+    reader.read_exact(&mut buf)?;
+    let version = u32::from_le_bytes(buf);
+
+    if version > max_version {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+
+    Ok(version)
+}
+
+/// Write message with little endian u32 length
+pub(crate) fn write_prefixed(
+    writer: &mut impl Write,
+    msg: &impl Message,
+) -> Result<(), FormatError> {
+    let bytes = msg.write_to_bytes()?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read message with little endian u32 length
+///
+/// Buffer grows with data actually read, so broken length doesn't allocate it upfront
+pub(crate) fn read_prefixed<M: Message>(reader: &mut impl Read) -> Result<M, FormatError> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    let len = u32::from_le_bytes(buf) as usize;
+
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(M::parse_from_bytes(&bytes)?)
+}
+
+pub(crate) fn save(
+    path: impl AsRef<Path>,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), FormatError>,
+) -> Result<(), FormatError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn load<T>(
+    path: impl AsRef<Path>,
+    read: impl FnOnce(&mut BufReader<File>) -> Result<T, FormatError>,
+) -> Result<T, FormatError> {
+    read(&mut BufReader::new(File::open(path)?))
+}
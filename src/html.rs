@@ -0,0 +1,219 @@
+use std::fmt::Write;
+
+use crate::color::Color;
+use crate::console::ConsoleContext;
+use crate::font::FontStyles;
+use crate::protos::qni_api::*;
+use crate::screen::{Line, ScreenState, Span};
+
+const STYLE: &str = "body{margin:0;background:#000;color:#c0c0c0;font-family:monospace}
+.qni-console{padding:8px}
+.qni-line{white-space:pre-wrap;min-height:1.2em}
+.qni-rule{border:0;border-top:1px solid currentColor;margin:0.6em 0}
+.qni-button{text-decoration:underline;cursor:default}
+.qni-button:hover{color:var(--qni-highlight,#ff0)}";
+
+/// Export console transcript as self-contained HTML document
+///
+/// Colors are treated as `0xAARRGGBB` and alpha is ignored.
+/// Buttons are rendered as inert elements with their value in `data-value`.
+#[derive(Clone, Debug)]
+pub struct HtmlExporter {
+    title: String,
+}
+
+impl Default for HtmlExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtmlExporter {
+    /// Create new HtmlExporter
+    pub fn new() -> Self {
+        Self {
+            title: "qni transcript".into(),
+        }
+    }
+
+    /// Set document title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Export all commands of console
+    pub fn export_console(&self, ctx: &ConsoleContext) -> String {
+        self.export_screen(&ScreenState::from_commands(&ctx.command_snapshot(0)))
+    }
+
+    /// Export commands
+    pub fn export_commands(&self, commands: &[ProgramCommand]) -> String {
+        self.export_screen(&ScreenState::from_commands(commands))
+    }
+
+    /// Export screen
+    pub fn export_screen(&self, screen: &ScreenState) -> String {
+        let mut out = String::new();
+
+        write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<div class=\"qni-console\">\n",
+            escape(&self.title),
+            STYLE
+        )
+        .unwrap();
+
+        for line in screen.iter_lines() {
+            write_line(&mut out, line);
+        }
+
+        out.push_str("</div>\n</body>\n</html>\n");
+
+        out
+    }
+}
+
+fn write_line(out: &mut String, line: &Line) {
+    if line.rule {
+        out.push_str("<hr class=\"qni-rule\">\n");
+        return;
+    }
+
+    let align = match line.align() {
+        TextAlign::LEFT => "left",
+        TextAlign::RIGHT => "right",
+        TextAlign::CENTER => "center",
+    };
+
+    write!(
+        out,
+        "<div class=\"qni-line\" style=\"text-align:{}\">",
+        align
+    )
+    .unwrap();
+
+    for span in &line.spans {
+        write_span(out, span);
+    }
+
+    out.push_str("</div>\n");
+}
+
+fn write_span(out: &mut String, span: &Span) {
+    let settings = &span.settings;
+    let mut style = String::new();
+
+    if let Some(color) = settings.text_color {
+        write!(style, "color:{};", css_color(color)).unwrap();
+    }
+
+    if let Some(color) = settings.back_color {
+        write!(style, "background-color:{};", css_color(color)).unwrap();
+    }
+
+    if let Some(font) = &settings.font {
+        if !font.font_family.is_empty() {
+            write!(
+                style,
+                "font-family:'{}',monospace;",
+                css_string(&font.font_family)
+            )
+            .unwrap();
+        }
+
+        if font.font_size > 0.0 {
+            write!(style, "font-size:{}px;", font.font_size).unwrap();
+        }
+    }
+
+    let styles = settings.font_styles();
+
+    if styles.contains(FontStyles::BOLD) {
+        style.push_str("font-weight:bold;");
+    }
+
+    if styles.contains(FontStyles::ITALIC) {
+        style.push_str("font-style:italic;");
+    }
+
+    match (
+        styles.contains(FontStyles::UNDERLINE),
+        styles.contains(FontStyles::STRIKETHROUGH),
+    ) {
+        (true, true) => style.push_str("text-decoration:underline line-through;"),
+        (true, false) => style.push_str("text-decoration:underline;"),
+        (false, true) => style.push_str("text-decoration:line-through;"),
+        (false, false) => {}
+    }
+
+    out.push_str("<span");
+
+    if let Some(value) = &span.button {
+        out.push_str(" class=\"qni-button\"");
+        write!(out, " data-value=\"{}\"", escape(&button_value(value))).unwrap();
+
+        if let Some(color) = settings.highlight_color {
+            write!(style, "--qni-highlight:{};", css_color(color)).unwrap();
+        }
+    }
+
+    if !style.is_empty() {
+        write!(out, " style=\"{}\"", escape(&style)).unwrap();
+    }
+
+    write!(out, ">{}</span>", escape(&span.text)).unwrap();
+}
+
+pub(crate) fn css_color(color: u32) -> String {
+    Color(color).to_css()
+}
+
+pub(crate) fn button_value(value: &InputResponse) -> String {
+    match value.data {
+        Some(InputResponse_oneof_data::EMPTY(_)) | None => String::new(),
+        Some(InputResponse_oneof_data::BOOLEAN(value)) => value.to_string(),
+        Some(InputResponse_oneof_data::STR(ref value)) => value.clone(),
+        Some(InputResponse_oneof_data::INT(value)) => value.to_string(),
+        Some(InputResponse_oneof_data::FLOAT(value)) => value.to_string(),
+        Some(InputResponse_oneof_data::DATE(ref value))
+        | Some(InputResponse_oneof_data::DATETIME(ref value)) => value.seconds.to_string(),
+        Some(InputResponse_oneof_data::TIME(ref value)) => value.seconds.to_string(),
+        Some(InputResponse_oneof_data::COLOR(value)) => css_color(value),
+    }
+}
+
+/// Escape text for inside of single quoted CSS string
+///
+/// Quotes, backslash and control characters are written as CSS hex escapes,
+/// so the string can't be closed by text even after HTML attribute decoding
+fn css_string(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_control() || matches!(c, '\'' | '"' | '\\' | '<' | '>' | '&') {
+            write!(ret, "\\{:x} ", c as u32).unwrap();
+        } else {
+            ret.push(c);
+        }
+    }
+
+    ret
+}
+
+fn escape(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&#39;"),
+            c => ret.push(c),
+        }
+    }
+
+    ret
+}
@@ -0,0 +1,182 @@
+use chrono::prelude::*;
+use protobuf::well_known_types::Duration;
+
+use crate::builder::to_timestamp;
+use crate::color::Color;
+use crate::protos::qni_api::*;
+
+/// Text input parse error
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum InputParseError {
+    /// Text is not valid for requested type
+    #[error("invalid {kind} input: {text}")]
+    Invalid { kind: &'static str, text: String },
+    /// Text is longer than max length of request
+    #[error("input is longer than {0} characters")]
+    TooLong(u32),
+    /// Text is not one of selectable options
+    #[error("{0} is not in options")]
+    NotInOptions(String),
+    /// Request doesn't have input type
+    #[error("request has no input type")]
+    NoInput,
+}
+
+/// Get name of requested input type
+pub fn input_kind(req: &InputRequest) -> &'static str {
+    match req.data {
+        Some(InputRequest_oneof_data::TOUCH(_)) => "touch",
+        Some(InputRequest_oneof_data::ENTER(_)) => "enter",
+        Some(InputRequest_oneof_data::ANYKEY(_)) => "anykey",
+        Some(InputRequest_oneof_data::BOOLEAN(_)) => "boolean",
+        Some(InputRequest_oneof_data::STR(_)) | Some(InputRequest_oneof_data::STR_MAX_LEN(_)) => {
+            "string"
+        }
+        Some(InputRequest_oneof_data::STR_SELECT(_)) => "select",
+        Some(InputRequest_oneof_data::INT(_)) | Some(InputRequest_oneof_data::INT_MAX_LEN(_)) => {
+            "integer"
+        }
+        Some(InputRequest_oneof_data::FLOAT(_))
+        | Some(InputRequest_oneof_data::FLOAT_MAX_LEN(_)) => "float",
+        Some(InputRequest_oneof_data::DATE(_)) => "date",
+        Some(InputRequest_oneof_data::DATETIME(_)) => "datetime",
+        Some(InputRequest_oneof_data::TIME(_)) => "time",
+        Some(InputRequest_oneof_data::COLOR(_)) => "color",
+        None => "none",
+    }
+}
+
+/// Check response value can answer request
+pub fn is_acceptable(req: &InputRequest, value: &InputResponse) -> bool {
+    use InputRequest_oneof_data as Req;
+    use InputResponse_oneof_data as Res;
+
+    match (&req.data, &value.data) {
+        (Some(Req::TOUCH(_)), _) | (Some(Req::ENTER(_)), _) | (Some(Req::ANYKEY(_)), _) => true,
+        (Some(Req::BOOLEAN(_)), Some(Res::BOOLEAN(_))) => true,
+        (Some(Req::STR(_)), Some(Res::STR(_))) => true,
+        (Some(Req::STR_MAX_LEN(len)), Some(Res::STR(text))) => {
+            text.chars().count() <= *len as usize
+        }
+        (Some(Req::STR_SELECT(options)), Some(Res::STR(text))) => options.get_data().contains(text),
+        (Some(Req::INT(_)), Some(Res::INT(_))) | (Some(Req::INT_MAX_LEN(_)), Some(Res::INT(_))) => {
+            true
+        }
+        (Some(Req::FLOAT(_)), Some(Res::FLOAT(_)))
+        | (Some(Req::FLOAT_MAX_LEN(_)), Some(Res::FLOAT(_))) => true,
+        (Some(Req::DATE(_)), Some(Res::DATE(_))) => true,
+        (Some(Req::DATETIME(_)), Some(Res::DATETIME(_))) => true,
+        (Some(Req::TIME(_)), Some(Res::TIME(_))) => true,
+        (Some(Req::COLOR(_)), Some(Res::COLOR(_))) => true,
+        _ => false,
+    }
+}
+
+/// Parse text typed by user as response of request
+///
+/// * BOOLEAN accepts `y`, `yes`, `true`, `1` and `n`, `no`, `false`, `0`
+/// * STR_SELECT accepts option text or 1-based option number
+/// * DATE accepts `YYYY-MM-DD`
+/// * DATETIME accepts RFC 3339 or `YYYY-MM-DD HH:MM[:SS]` in UTC
+/// * TIME accepts `HH:MM[:SS]`, it is sent as duration since midnight
+/// * COLOR accepts `#RRGGBB` or `#AARRGGBB`, alpha is `FF` when omitted
+pub fn parse_input(req: &InputRequest, text: &str) -> Result<InputResponse, InputParseError> {
+    let mut res = InputResponse::new();
+    let kind = input_kind(req);
+    let invalid = || InputParseError::Invalid {
+        kind,
+        text: text.into(),
+    };
+    let check_len = |max_len: u32| {
+        if text.chars().count() > max_len as usize {
+            Err(InputParseError::TooLong(max_len))
+        } else {
+            Ok(())
+        }
+    };
+
+    match req.data {
+        Some(InputRequest_oneof_data::TOUCH(_))
+        | Some(InputRequest_oneof_data::ENTER(_))
+        | Some(InputRequest_oneof_data::ANYKEY(_)) => {
+            res.mut_EMPTY();
+        }
+        Some(InputRequest_oneof_data::BOOLEAN(_)) => {
+            let value = match text.trim().to_lowercase().as_str() {
+                "y" | "yes" | "true" | "1" => true,
+                "n" | "no" | "false" | "0" => false,
+                _ => return Err(invalid()),
+            };
+            res.set_BOOLEAN(value);
+        }
+        Some(InputRequest_oneof_data::STR(_)) => res.set_STR(text.into()),
+        Some(InputRequest_oneof_data::STR_MAX_LEN(max_len)) => {
+            check_len(max_len)?;
+            res.set_STR(text.into());
+        }
+        Some(InputRequest_oneof_data::STR_SELECT(ref options)) => {
+            let options = options.get_data();
+
+            let selected = options
+                .iter()
+                .find(|option| option.as_str() == text)
+                .or_else(|| {
+                    text.trim()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|num| num.checked_sub(1))
+                        .and_then(|idx| options.get(idx))
+                })
+                .ok_or_else(|| InputParseError::NotInOptions(text.into()))?;
+
+            res.set_STR(selected.clone());
+        }
+        Some(InputRequest_oneof_data::INT(_)) => {
+            res.set_INT(text.trim().parse().map_err(|_| invalid())?);
+        }
+        Some(InputRequest_oneof_data::INT_MAX_LEN(max_len)) => {
+            check_len(max_len)?;
+            res.set_INT(text.trim().parse().map_err(|_| invalid())?);
+        }
+        Some(InputRequest_oneof_data::FLOAT(_)) => {
+            res.set_FLOAT(text.trim().parse().map_err(|_| invalid())?);
+        }
+        Some(InputRequest_oneof_data::FLOAT_MAX_LEN(max_len)) => {
+            check_len(max_len)?;
+            res.set_FLOAT(text.trim().parse().map_err(|_| invalid())?);
+        }
+        Some(InputRequest_oneof_data::DATE(_)) => {
+            let date = NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+            res.set_DATE(to_timestamp(date.and_hms_opt(0, 0, 0).unwrap().and_utc()));
+        }
+        Some(InputRequest_oneof_data::DATETIME(_)) => {
+            let text = text.trim();
+            let datetime = DateTime::parse_from_rfc3339(text)
+                .map(|datetime| datetime.with_timezone(&Utc))
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").map(|d| d.and_utc())
+                })
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").map(|d| d.and_utc())
+                })
+                .map_err(|_| invalid())?;
+            res.set_DATETIME(to_timestamp(datetime));
+        }
+        Some(InputRequest_oneof_data::TIME(_)) => {
+            let text = text.trim();
+            let time = NaiveTime::parse_from_str(text, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+                .map_err(|_| invalid())?;
+            let mut duration = Duration::new();
+            duration.set_seconds(i64::from(time.num_seconds_from_midnight()));
+            res.set_TIME(duration);
+        }
+        Some(InputRequest_oneof_data::COLOR(_)) => {
+            let color: Color = text.parse().map_err(|_| invalid())?;
+            res.set_COLOR(color.into());
+        }
+        None => return Err(InputParseError::NoInput),
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,190 @@
+//! Column layout helpers assuming monospace font
+//!
+//! East Asian wide characters like Hangul, Kana and CJK ideographs take two cells,
+//! combining marks and control characters take none.
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::protos::qni_api::*;
+
+/// Count of cells character takes, widths follow Unicode East Asian Width
+pub fn char_width(c: char) -> usize {
+    // control characters have no width
+    c.width().unwrap_or(0)
+}
+
+/// Count of cells text takes
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Longest prefix of text which fits in width
+///
+/// Wide character which crosses width is excluded
+pub fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+
+    for (index, c) in text.char_indices() {
+        used += char_width(c);
+
+        if used > width {
+            return &text[..index];
+        }
+    }
+
+    text
+}
+
+/// Truncate text to width, then fill rest of width with spaces by align
+pub fn pad_to_width(text: &str, width: usize, align: TextAlign) -> String {
+    let text = truncate_to_width(text, width);
+    let padding = width - display_width(text);
+    let left = match align {
+        TextAlign::LEFT => 0,
+        TextAlign::RIGHT => padding,
+        TextAlign::CENTER => padding / 2,
+    };
+
+    let mut ret = String::with_capacity(text.len() + padding);
+    ret.extend(std::iter::repeat_n(' ', left));
+    ret.push_str(text);
+    ret.extend(std::iter::repeat_n(' ', padding - left));
+    ret
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Column {
+    align: TextAlign,
+    width: Option<usize>,
+}
+
+/// Text table printed with PRINT_LINE and DRAW_LINE
+///
+/// Column width is widest cell unless it is set. With border, columns are separated
+/// with `|` and rules are drawn around header and table.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    columns: Vec<Column>,
+    border: bool,
+}
+
+impl Table {
+    /// Create new empty Table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set header row
+    pub fn header<S: Into<String>>(mut self, cells: impl IntoIterator<Item = S>) -> Self {
+        self.header = Some(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Append row
+    pub fn row<S: Into<String>>(mut self, cells: impl IntoIterator<Item = S>) -> Self {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set align of column, default is LEFT
+    pub fn align(mut self, column: usize, align: TextAlign) -> Self {
+        self.column(column).align = align;
+        self
+    }
+
+    /// Set fixed width of column, longer cells are truncated
+    pub fn width(mut self, column: usize, width: usize) -> Self {
+        self.column(column).width = Some(width);
+        self
+    }
+
+    /// Draw border
+    pub fn border(mut self, border: bool) -> Self {
+        self.border = border;
+        self
+    }
+
+    fn column(&mut self, column: usize) -> &mut Column {
+        if self.columns.len() <= column {
+            self.columns.resize(
+                column + 1,
+                Column {
+                    align: TextAlign::LEFT,
+                    width: None,
+                },
+            );
+        }
+
+        &mut self.columns[column]
+    }
+
+    fn all_rows(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.header.iter().chain(&self.rows)
+    }
+
+    /// Width of each column
+    pub fn column_widths(&self) -> Vec<usize> {
+        let count = self.all_rows().map(Vec::len).max().unwrap_or(0);
+
+        (0..count)
+            .map(
+                |column| match self.columns.get(column).and_then(|c| c.width) {
+                    Some(width) => width,
+                    None => self
+                        .all_rows()
+                        .filter_map(|row| row.get(column))
+                        .map(|cell| display_width(cell))
+                        .max()
+                        .unwrap_or(0),
+                },
+            )
+            .collect()
+    }
+
+    fn format_row(&self, row: &[String], widths: &[usize]) -> String {
+        let cells = widths.iter().enumerate().map(|(column, &width)| {
+            let cell = row.get(column).map_or("", String::as_str);
+            let align = self
+                .columns
+                .get(column)
+                .map_or(TextAlign::LEFT, |c| c.align);
+            pad_to_width(cell, width, align)
+        });
+
+        if self.border {
+            format!("| {} |", cells.collect::<Vec<_>>().join(" | "))
+        } else {
+            cells.collect::<Vec<_>>().join("  ").trim_end().to_string()
+        }
+    }
+
+    /// Commands printing table
+    pub fn commands(&self) -> Vec<ProgramCommand> {
+        let widths = self.column_widths();
+        let mut commands = Vec::new();
+
+        if self.border {
+            commands.push(ProgramCommand::draw_line());
+        }
+
+        if let Some(header) = &self.header {
+            commands.push(ProgramCommand::print_line(self.format_row(header, &widths)));
+
+            if self.border {
+                commands.push(ProgramCommand::draw_line());
+            }
+        }
+
+        for row in &self.rows {
+            commands.push(ProgramCommand::print_line(self.format_row(row, &widths)));
+        }
+
+        if self.border && !self.rows.is_empty() {
+            commands.push(ProgramCommand::draw_line());
+        }
+
+        commands
+    }
+}
@@ -1,13 +1,51 @@
+pub mod builder;
+pub mod clock;
+pub mod color;
 pub mod connector;
 pub mod console;
+pub mod convert;
+pub mod event;
+pub mod font;
+mod format;
+pub mod html;
+pub mod input;
+pub mod layout;
+pub mod log;
+pub mod markup;
+pub mod program;
 pub mod protos;
+pub mod record;
+pub mod registry;
+pub mod render;
+pub mod screen;
+pub mod session;
+pub mod testing;
+pub mod transport;
+#[cfg(feature = "tty")]
+pub mod tty;
 
 pub mod c_api;
 
 pub mod prelude {
     pub use crate::c_api;
+    pub use crate::clock::*;
+    pub use crate::color::*;
     pub use crate::connector::*;
     pub use crate::console::*;
+    pub use crate::convert::*;
+    pub use crate::event::*;
+    pub use crate::font::*;
+    pub use crate::html::*;
+    pub use crate::input::*;
+    pub use crate::layout::*;
+    pub use crate::log::*;
+    pub use crate::markup::*;
+    pub use crate::program::*;
     pub use crate::protos::qni_api;
+    pub use crate::record::*;
+    pub use crate::registry::*;
+    pub use crate::render::*;
+    pub use crate::screen::*;
+    pub use crate::session::*;
     pub use protobuf;
 }
@@ -0,0 +1,228 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use protobuf::Message;
+
+use crate::protos::qni_api::*;
+
+const CHUNK_SIZE: usize = 256;
+
+/// Key of `ProgramCommandArray.commands`, field 1 with length delimited wire type
+const COMMANDS_KEY: u8 = 0x0A;
+
+struct Entry {
+    command: ProgramCommand,
+    /// `commands` field bytes of ProgramCommandArray, encoded when first needed
+    encoded: OnceLock<Vec<u8>>,
+}
+
+impl Entry {
+    fn encoded(&self) -> &[u8] {
+        self.encoded.get_or_init(|| {
+            let bytes = self
+                .command
+                .write_length_delimited_to_bytes()
+                .expect("ProgramCommand has no required field");
+            let mut encoded = Vec::with_capacity(bytes.len() + 1);
+            encoded.push(COMMANDS_KEY);
+            encoded.extend_from_slice(&bytes);
+            encoded
+        })
+    }
+}
+
+struct Chunk {
+    slots: Box<[OnceLock<Entry>]>,
+}
+
+impl Chunk {
+    fn new() -> Self {
+        Self {
+            slots: (0..CHUNK_SIZE).map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    fn get(&self, slot: usize) -> &Entry {
+        // slots under published length are always set
+        self.slots[slot].get().unwrap()
+    }
+}
+
+/// Append-only command log made of fixed size chunks
+///
+/// Appends are serialized by a mutex and take write lock of chunk list only when new chunk is added.
+/// Snapshot holds read lock of chunk list only while cloning `Arc` of chunks,
+/// then commands are read without lock because appended command is never changed.
+/// Encoded bytes of each command are cached when snapshot is encoded first time.
+pub struct CommandLog {
+    chunks: RwLock<Vec<Arc<Chunk>>>,
+    len: AtomicUsize,
+    tail: Mutex<Option<Arc<Chunk>>>,
+}
+
+impl Default for CommandLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandLog {
+    /// Create new CommandLog
+    pub fn new() -> Self {
+        Self {
+            chunks: RwLock::new(Vec::new()),
+            len: AtomicUsize::new(0),
+            tail: Mutex::new(None),
+        }
+    }
+
+    /// Create CommandLog from commands
+    pub fn from_commands(commands: impl IntoIterator<Item = ProgramCommand>) -> Self {
+        let log = Self::new();
+
+        for command in commands {
+            log.append(command);
+        }
+
+        log
+    }
+
+    /// Get count of commands
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Log has no command
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append command and return its index
+    pub fn append(&self, command: ProgramCommand) -> usize {
+        self.append_all(Some(command)).start
+    }
+
+    /// Append commands and return range of their indices
+    ///
+    /// Readers see whole batch at once
+    pub fn append_all(&self, commands: impl IntoIterator<Item = ProgramCommand>) -> Range<usize> {
+        let mut tail = self.tail.lock().unwrap();
+        let start = self.len.load(Ordering::Relaxed);
+        let mut index = start;
+
+        for command in commands {
+            let slot = index % CHUNK_SIZE;
+
+            if slot == 0 {
+                let chunk = Arc::new(Chunk::new());
+                self.chunks.write().unwrap().push(chunk.clone());
+                *tail = Some(chunk);
+            }
+
+            let _ = tail.as_ref().unwrap().slots[slot].set(Entry {
+                command,
+                encoded: OnceLock::new(),
+            });
+
+            index += 1;
+        }
+
+        self.len.store(index, Ordering::Release);
+
+        start..index
+    }
+
+    /// Clone command at index
+    pub fn get(&self, index: usize) -> Option<ProgramCommand> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let chunk = self.chunks.read().unwrap()[index / CHUNK_SIZE].clone();
+        Some(chunk.get(index % CHUNK_SIZE).command.clone())
+    }
+
+    /// Take snapshot of commands from index to current end
+    pub fn snapshot(&self, from: usize) -> CommandSnapshot {
+        let to = self.len();
+        let from = from.min(to);
+
+        let chunks = if from == to {
+            Vec::new()
+        } else {
+            self.chunks.read().unwrap()[from / CHUNK_SIZE..to.div_ceil(CHUNK_SIZE)].to_vec()
+        };
+
+        CommandSnapshot {
+            chunks,
+            offset: from % CHUNK_SIZE,
+            len: to - from,
+        }
+    }
+}
+
+/// Commands in range of CommandLog, shares data with log
+#[derive(Clone)]
+pub struct CommandSnapshot {
+    chunks: Vec<Arc<Chunk>>,
+    offset: usize,
+    len: usize,
+}
+
+impl CommandSnapshot {
+    /// Get count of commands
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Snapshot has no command
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn entry(&self, index: usize) -> Option<&Entry> {
+        if index >= self.len {
+            return None;
+        }
+
+        let index = self.offset + index;
+        Some(self.chunks[index / CHUNK_SIZE].get(index % CHUNK_SIZE))
+    }
+
+    /// Get command at index of snapshot
+    pub fn get(&self, index: usize) -> Option<&ProgramCommand> {
+        self.entry(index).map(|entry| &entry.command)
+    }
+
+    /// Iterate commands
+    pub fn iter(&self) -> impl Iterator<Item = &ProgramCommand> {
+        (0..self.len).map(move |index| self.get(index).unwrap())
+    }
+
+    /// Encode commands as ProgramCommandArray from cached bytes
+    pub fn encode_array(&self) -> Vec<u8> {
+        let entries = || (0..self.len).map(move |index| self.entry(index).unwrap().encoded());
+        let mut bytes = Vec::with_capacity(entries().map(<[u8]>::len).sum());
+
+        for encoded in entries() {
+            bytes.extend_from_slice(encoded);
+        }
+
+        bytes
+    }
+
+    /// Clone commands to Vec
+    pub fn to_vec(&self) -> Vec<ProgramCommand> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a CommandSnapshot {
+    type Item = &'a ProgramCommand;
+    type IntoIter = Box<dyn Iterator<Item = &'a ProgramCommand> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
@@ -0,0 +1,273 @@
+//! Inline markup for styled print
+//!
+//! | Tag | Effect |
+//! |-----|--------|
+//! | `[color=#ff0000]` `[bg=navy]` `[hl=yellow]` | text, back, highlight color |
+//! | `[b]` `[i]` `[u]` `[s]` | bold, italic, underline, strikethrough |
+//! | `[font=Serif]` `[size=20]` | font family, font size |
+//! | `[button=1]text[/button]` | PRINT_BUTTON, value is INT if it is integer otherwise STR |
+//!
+//! Every tag is closed with `[/name]`, and settings are restored when tag is closed.
+//! Font tags change only their own field of current font, font which was never set
+//! is restored to empty font. Color which was never set can't be restored,
+//! so set base color before using color tags.
+//! `[[` prints `[`.
+//!
+//! ```
+//! use qni_core_rs::prelude::*;
+//!
+//! let commands = parse_markup(
+//!     "[color=#ff0000]Danger[/color] [b]now[/b] [button=1]Fight[/button]",
+//!     &ConsoleSettings::default(),
+//! )
+//! .unwrap();
+//! assert_eq!(8, commands.len());
+//! ```
+
+use crate::color::Color;
+use crate::font::FontStyles;
+use crate::protos::qni_api::*;
+use crate::screen::ConsoleSettings;
+
+/// Markup parse error, positions are byte offsets of tag
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum MarkupError {
+    /// `[` without `]`
+    #[error("tag at {0} is not terminated with ']'")]
+    UnterminatedTag(usize),
+    /// Tag name is not known
+    #[error("unknown tag [{tag}] at {position}")]
+    UnknownTag { tag: String, position: usize },
+    /// Tag needs value like `[color=red]`
+    #[error("tag [{tag}] at {position} needs value")]
+    MissingValue { tag: String, position: usize },
+    /// Value of tag is invalid
+    #[error("invalid value {value:?} of tag [{tag}] at {position}")]
+    InvalidValue {
+        tag: String,
+        value: String,
+        position: usize,
+    },
+    /// Close tag without open tag
+    #[error("[/{tag}] at {position} has no open tag")]
+    UnexpectedClose { tag: String, position: usize },
+    /// Close tag doesn't match last open tag
+    #[error("expected [/{expected}] but found [/{found}] at {position}")]
+    MismatchedClose {
+        expected: String,
+        found: String,
+        position: usize,
+    },
+    /// Open tag is not closed until end of text
+    #[error("[{tag}] at {position} is not closed")]
+    Unclosed { tag: String, position: usize },
+    /// Button text can't have tag
+    #[error("tag at {0} is inside of button")]
+    TagInButton(usize),
+}
+
+struct OpenTag {
+    name: String,
+    position: usize,
+    /// Settings before tag is opened
+    saved: ConsoleSettings,
+}
+
+struct Parser {
+    commands: Vec<ProgramCommand>,
+    settings: ConsoleSettings,
+    stack: Vec<OpenTag>,
+    text: String,
+    button: Option<InputResponse>,
+}
+
+impl Parser {
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            self.commands
+                .push(ProgramCommand::print(std::mem::take(&mut self.text)));
+        }
+    }
+
+    fn tag(&mut self, tag: &str, position: usize) -> Result<(), MarkupError> {
+        if let Some(name) = tag.strip_prefix('/') {
+            return self.close(name.trim(), position);
+        }
+
+        if self.button.is_some() {
+            return Err(MarkupError::TagInButton(position));
+        }
+
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (tag.trim(), None),
+        };
+        let name = name.to_ascii_lowercase();
+
+        let invalid = |value: &str| MarkupError::InvalidValue {
+            tag: name.clone(),
+            value: value.into(),
+            position,
+        };
+        let required = || {
+            value.ok_or_else(|| MarkupError::MissingValue {
+                tag: name.clone(),
+                position,
+            })
+        };
+
+        let style = match name.as_str() {
+            "b" => Some(FontStyles::BOLD),
+            "i" => Some(FontStyles::ITALIC),
+            "u" => Some(FontStyles::UNDERLINE),
+            "s" => Some(FontStyles::STRIKETHROUGH),
+            _ => None,
+        };
+
+        self.flush_text();
+
+        let saved = self.settings.clone();
+
+        match name.as_str() {
+            "color" | "bg" | "hl" => {
+                let value = required()?;
+                let color: Color = value.parse().map_err(|_| invalid(value))?;
+                let target = match name.as_str() {
+                    "color" => &mut self.settings.text_color,
+                    "bg" => &mut self.settings.back_color,
+                    _ => &mut self.settings.highlight_color,
+                };
+                *target = Some(color.into());
+            }
+            "font" => {
+                let value = required()?;
+                self.font().set_font_family(value.into());
+            }
+            "size" => {
+                let value = required()?;
+                let size = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|size| *size > 0.0)
+                    .ok_or_else(|| invalid(value))?;
+                self.font().set_font_size(size);
+            }
+            "button" => {
+                let value = required()?;
+                self.button = Some(match value.parse::<i32>() {
+                    Ok(value) => value.into(),
+                    Err(_) => value.into(),
+                });
+            }
+            _ => match style {
+                Some(style) => {
+                    if let Some(value) = value {
+                        return Err(invalid(value));
+                    }
+
+                    let font = self.font();
+                    font.set_font_style((font.styles() | style).bits());
+                }
+                None => {
+                    return Err(MarkupError::UnknownTag {
+                        tag: name,
+                        position,
+                    })
+                }
+            },
+        }
+
+        self.commands.extend(saved.update_commands(&self.settings));
+        self.stack.push(OpenTag {
+            name,
+            position,
+            saved,
+        });
+
+        Ok(())
+    }
+
+    fn close(&mut self, name: &str, position: usize) -> Result<(), MarkupError> {
+        let open = self
+            .stack
+            .pop()
+            .ok_or_else(|| MarkupError::UnexpectedClose {
+                tag: name.into(),
+                position,
+            })?;
+
+        if !open.name.eq_ignore_ascii_case(name) {
+            return Err(MarkupError::MismatchedClose {
+                expected: open.name,
+                found: name.into(),
+                position,
+            });
+        }
+
+        if let Some(value) = self.button.take() {
+            self.commands.push(ProgramCommand::button(
+                std::mem::take(&mut self.text),
+                value,
+            ));
+            return Ok(());
+        }
+
+        self.flush_text();
+        self.commands
+            .extend(self.settings.update_commands(&open.saved));
+        self.settings = open.saved;
+
+        Ok(())
+    }
+
+    /// Current font to change
+    ///
+    /// When font was never set, font has only changed fields.
+    /// Empty family and zero size keep frontend default.
+    fn font(&mut self) -> &mut Font {
+        self.settings.font.get_or_insert_with(Font::new)
+    }
+}
+
+/// Parse markup into commands, settings are restored to base at the end
+pub fn parse_markup(
+    text: &str,
+    base: &ConsoleSettings,
+) -> Result<Vec<ProgramCommand>, MarkupError> {
+    let mut parser = Parser {
+        commands: Vec::new(),
+        settings: base.clone(),
+        stack: Vec::new(),
+        text: String::new(),
+        button: None,
+    };
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+
+        if rest.starts_with("[[") {
+            parser.text.push('[');
+            pos += 2;
+        } else if rest.starts_with('[') {
+            let end = rest.find(']').ok_or(MarkupError::UnterminatedTag(pos))?;
+            parser.tag(&rest[1..end], pos)?;
+            pos += end + 1;
+        } else {
+            let end = rest.find('[').unwrap_or(rest.len());
+            parser.text.push_str(&rest[..end]);
+            pos += end;
+        }
+    }
+
+    if let Some(open) = parser.stack.pop() {
+        return Err(MarkupError::Unclosed {
+            tag: open.name,
+            position: open.position,
+        });
+    }
+
+    parser.flush_text();
+
+    Ok(parser.commands)
+}
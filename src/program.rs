@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use crate::console::{ConsoleContext, WaitError};
+use crate::layout::{display_width, pad_to_width, Table};
+use crate::markup::{parse_markup, MarkupError};
+use crate::protos::qni_api::*;
+
+/// Button layout of `Console::menu`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuLayout {
+    /// One button per line
+    Vertical,
+    /// Every button in one line
+    Horizontal,
+    /// Buttons in columns, padded to same width
+    Grid(usize),
+}
+
+/// Options of `Console::menu_with`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MenuOptions {
+    layout: MenuLayout,
+    numbered: bool,
+}
+
+impl Default for MenuOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MenuOptions {
+    /// Vertical layout without number
+    pub fn new() -> Self {
+        Self {
+            layout: MenuLayout::Vertical,
+            numbered: false,
+        }
+    }
+
+    /// Set layout
+    pub fn layout(mut self, layout: MenuLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Prefix labels with `[n] ` so item can be selected by typing number
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.numbered = numbered;
+        self
+    }
+}
+
+/// Program side console API over ConsoleContext
+#[derive(Clone)]
+pub struct Console {
+    ctx: Arc<ConsoleContext>,
+}
+
+impl Console {
+    /// Create Console of context
+    pub fn new(ctx: Arc<ConsoleContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// Get ConsoleContext
+    #[inline]
+    pub fn context(&self) -> &Arc<ConsoleContext> {
+        &self.ctx
+    }
+
+    /// Print text
+    pub fn print(&self, text: impl Into<String>) {
+        self.ctx.append_command(ProgramCommand::print(text));
+    }
+
+    /// Print text and complete line
+    pub fn print_line(&self, text: impl Into<String>) {
+        self.ctx.append_command(ProgramCommand::print_line(text));
+    }
+
+    /// Print button
+    pub fn print_button(&self, text: impl Into<String>, value: impl Into<InputResponse>) {
+        self.ctx.append_command(ProgramCommand::button(text, value));
+    }
+
+    /// Complete line
+    pub fn new_line(&self) {
+        self.ctx.append_command(ProgramCommand::new_line());
+    }
+
+    /// Draw horizontal rule
+    pub fn draw_line(&self) {
+        self.ctx.append_command(ProgramCommand::draw_line());
+    }
+
+    /// Print text padded or truncated to display width
+    pub fn print_padded(&self, text: &str, width: usize, align: TextAlign) {
+        self.print(pad_to_width(text, width, align));
+    }
+
+    /// Print table
+    pub fn print_table(&self, table: &Table) {
+        self.ctx.append_commands(table.commands());
+    }
+
+    /// Print buttons of items and wait until one of them is selected
+    ///
+    /// Button values are 1-based INT, so typing number also selects item.
+    /// `WaitError::EmptyMenu` is returned if items is empty.
+    pub fn menu<S: AsRef<str>, T: Clone>(&self, items: &[(S, T)]) -> Result<T, WaitError> {
+        self.menu_with(items, MenuOptions::new())
+    }
+
+    /// `menu` with options
+    pub fn menu_with<S: AsRef<str>, T: Clone>(
+        &self,
+        items: &[(S, T)],
+        options: MenuOptions,
+    ) -> Result<T, WaitError> {
+        let labels: Vec<&str> = items.iter().map(|(label, _)| label.as_ref()).collect();
+        let index = self.menu_index(&labels, options)?;
+        Ok(items[index].1.clone())
+    }
+
+    /// Print buttons of labels and wait until one of them is selected, return its index
+    pub fn menu_index(&self, labels: &[&str], options: MenuOptions) -> Result<usize, WaitError> {
+        if labels.is_empty() {
+            return Err(WaitError::EmptyMenu);
+        }
+
+        self.ctx.append_commands(menu_commands(labels, options));
+
+        loop {
+            let mut res = self.ctx.wait_console(ProgramRequest::input_int())?;
+
+            if !res.has_OK_INPUT() {
+                continue;
+            }
+
+            if let Ok(number) = i32::try_from(res.take_OK_INPUT()) {
+                if number >= 1 && number as usize <= labels.len() {
+                    return Ok(number as usize - 1);
+                }
+            }
+        }
+    }
+
+    /// Print markup, nothing is printed when markup is malformed
+    ///
+    /// See [`markup`](crate::markup) for tags
+    pub fn print_markup(&self, markup: &str) -> Result<(), MarkupError> {
+        let commands = parse_markup(markup, &self.ctx.settings())?;
+        self.ctx.append_commands(commands);
+        Ok(())
+    }
+}
+
+impl From<Arc<ConsoleContext>> for Console {
+    fn from(ctx: Arc<ConsoleContext>) -> Self {
+        Self::new(ctx)
+    }
+}
+
+fn menu_commands(labels: &[&str], options: MenuOptions) -> Vec<ProgramCommand> {
+    let texts: Vec<String> = labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            if options.numbered {
+                format!("[{}] {}", index + 1, label)
+            } else {
+                label.to_string()
+            }
+        })
+        .collect();
+    let mut commands = Vec::new();
+
+    match options.layout {
+        MenuLayout::Vertical => {
+            for (index, text) in texts.into_iter().enumerate() {
+                commands.push(ProgramCommand::button(text, index as i32 + 1));
+                commands.push(ProgramCommand::new_line());
+            }
+        }
+        MenuLayout::Horizontal => {
+            for (index, text) in texts.into_iter().enumerate() {
+                if index > 0 {
+                    commands.push(ProgramCommand::print("  "));
+                }
+
+                commands.push(ProgramCommand::button(text, index as i32 + 1));
+            }
+
+            commands.push(ProgramCommand::new_line());
+        }
+        MenuLayout::Grid(columns) => {
+            let columns = columns.max(1);
+            let width = texts
+                .iter()
+                .map(|text| display_width(text))
+                .max()
+                .unwrap_or(0);
+
+            for (index, text) in texts.iter().enumerate() {
+                let last = index % columns == columns - 1 || index == texts.len() - 1;
+
+                if index % columns != 0 {
+                    commands.push(ProgramCommand::print("  "));
+                }
+
+                // pad inside of button so every column starts at same cell
+                let text = if last {
+                    text.clone()
+                } else {
+                    pad_to_width(text, width, TextAlign::LEFT)
+                };
+                commands.push(ProgramCommand::button(text, index as i32 + 1));
+
+                if last {
+                    commands.push(ProgramCommand::new_line());
+                }
+            }
+        }
+    }
+
+    commands
+}
@@ -1,2 +1,8 @@
 /// Types for communicate frontEnds and programs
+#[allow(
+    unknown_lints,
+    unused_parens,
+    renamed_and_removed_lints,
+    mismatched_lifetime_syntaxes
+)]
 pub mod qni_api;
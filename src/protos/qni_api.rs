@@ -5534,6 +5534,203 @@ impl ::protobuf::reflect::ProtobufValue for ProgramResponse {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct ProgramExit {
+    // message fields
+    pub code: i32,
+    pub reason: ::std::string::String,
+    // special fields
+    #[cfg_attr(feature = "with-serde", serde(skip))]
+    pub unknown_fields: ::protobuf::UnknownFields,
+    #[cfg_attr(feature = "with-serde", serde(skip))]
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ProgramExit {
+    fn default() -> &'a ProgramExit {
+        <ProgramExit as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ProgramExit {
+    pub fn new() -> ProgramExit {
+        ::std::default::Default::default()
+    }
+
+    // int32 code = 1;
+
+
+    pub fn get_code(&self) -> i32 {
+        self.code
+    }
+    pub fn clear_code(&mut self) {
+        self.code = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_code(&mut self, v: i32) {
+        self.code = v;
+    }
+
+    // string reason = 2;
+
+
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+    pub fn clear_reason(&mut self) {
+        self.reason.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_reason(&mut self, v: ::std::string::String) {
+        self.reason = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_reason(&mut self) -> &mut ::std::string::String {
+        &mut self.reason
+    }
+
+    // Take field
+    pub fn take_reason(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.reason, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for ProgramExit {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int32()?;
+                    self.code = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.reason)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.code != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.code, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.reason.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.reason);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.code != 0 {
+            os.write_int32(1, self.code)?;
+        }
+        if !self.reason.is_empty() {
+            os.write_string(2, &self.reason)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ProgramExit {
+        ProgramExit::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt32>(
+                "code",
+                |m: &ProgramExit| { &m.code },
+                |m: &mut ProgramExit| { &mut m.code },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "reason",
+                |m: &ProgramExit| { &m.reason },
+                |m: &mut ProgramExit| { &mut m.reason },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<ProgramExit>(
+                "ProgramExit",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static ProgramExit {
+        static instance: ::protobuf::rt::LazyV2<ProgramExit> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(ProgramExit::new)
+    }
+}
+
+impl ::protobuf::Clear for ProgramExit {
+    fn clear(&mut self) {
+        self.code = 0;
+        self.reason.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ProgramExit {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ProgramExit {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
 #[derive(PartialEq,Clone,Default)]
 #[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct ProgramMessage {
@@ -5558,6 +5755,9 @@ pub enum ProgramMessage_oneof_data {
     REQ(ProgramRequest),
     RES(ProgramResponse),
     ACCEPT_RES(u32),
+    DEFAULT_RES(ConsoleResponse),
+    WITHDRAW_REQ(u32),
+    EXIT(ProgramExit),
 }
 
 impl ProgramMessage {
@@ -5687,6 +5887,129 @@ impl ProgramMessage {
     pub fn set_ACCEPT_RES(&mut self, v: u32) {
         self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::ACCEPT_RES(v))
     }
+
+    // .qni.api.ConsoleResponse DEFAULT_RES = 13;
+
+
+    pub fn get_DEFAULT_RES(&self) -> &ConsoleResponse {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(ref v)) => v,
+            _ => <ConsoleResponse as ::protobuf::Message>::default_instance(),
+        }
+    }
+    pub fn clear_DEFAULT_RES(&mut self) {
+        self.data = ::std::option::Option::None;
+    }
+
+    pub fn has_DEFAULT_RES(&self) -> bool {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_DEFAULT_RES(&mut self, v: ConsoleResponse) {
+        self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_DEFAULT_RES(&mut self) -> &mut ConsoleResponse {
+        if let ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(_)) = self.data {
+        } else {
+            self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(ConsoleResponse::new()));
+        }
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_DEFAULT_RES(&mut self) -> ConsoleResponse {
+        if self.has_DEFAULT_RES() {
+            match self.data.take() {
+                ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            ConsoleResponse::new()
+        }
+    }
+
+    // uint32 WITHDRAW_REQ = 14;
+
+
+    pub fn get_WITHDRAW_REQ(&self) -> u32 {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::WITHDRAW_REQ(v)) => v,
+            _ => 0,
+        }
+    }
+    pub fn clear_WITHDRAW_REQ(&mut self) {
+        self.data = ::std::option::Option::None;
+    }
+
+    pub fn has_WITHDRAW_REQ(&self) -> bool {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::WITHDRAW_REQ(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_WITHDRAW_REQ(&mut self, v: u32) {
+        self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::WITHDRAW_REQ(v))
+    }
+
+    // .qni.api.ProgramExit EXIT = 15;
+
+
+    pub fn get_EXIT(&self) -> &ProgramExit {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(ref v)) => v,
+            _ => <ProgramExit as ::protobuf::Message>::default_instance(),
+        }
+    }
+    pub fn clear_EXIT(&mut self) {
+        self.data = ::std::option::Option::None;
+    }
+
+    pub fn has_EXIT(&self) -> bool {
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_EXIT(&mut self, v: ProgramExit) {
+        self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_EXIT(&mut self) -> &mut ProgramExit {
+        if let ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(_)) = self.data {
+        } else {
+            self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(ProgramExit::new()));
+        }
+        match self.data {
+            ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_EXIT(&mut self) -> ProgramExit {
+        if self.has_EXIT() {
+            match self.data.take() {
+                ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            ProgramExit::new()
+        }
+    }
 }
 
 impl ::protobuf::Message for ProgramMessage {
@@ -5701,6 +6024,16 @@ impl ::protobuf::Message for ProgramMessage {
                 return false;
             }
         }
+        if let Some(ProgramMessage_oneof_data::DEFAULT_RES(ref v)) = self.data {
+            if !v.is_initialized() {
+                return false;
+            }
+        }
+        if let Some(ProgramMessage_oneof_data::EXIT(ref v)) = self.data {
+            if !v.is_initialized() {
+                return false;
+            }
+        }
         true
     }
 
@@ -5726,6 +6059,24 @@ impl ::protobuf::Message for ProgramMessage {
                     }
                     self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::ACCEPT_RES(is.read_uint32()?));
                 },
+                13 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::DEFAULT_RES(is.read_message()?));
+                },
+                14 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::WITHDRAW_REQ(is.read_uint32()?));
+                },
+                15 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.data = ::std::option::Option::Some(ProgramMessage_oneof_data::EXIT(is.read_message()?));
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -5751,6 +6102,17 @@ impl ::protobuf::Message for ProgramMessage {
                 &ProgramMessage_oneof_data::ACCEPT_RES(v) => {
                     my_size += ::protobuf::rt::value_size(12, v, ::protobuf::wire_format::WireTypeVarint);
                 },
+                &ProgramMessage_oneof_data::DEFAULT_RES(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+                },
+                &ProgramMessage_oneof_data::WITHDRAW_REQ(v) => {
+                    my_size += ::protobuf::rt::value_size(14, v, ::protobuf::wire_format::WireTypeVarint);
+                },
+                &ProgramMessage_oneof_data::EXIT(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+                },
             };
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
@@ -5774,6 +6136,19 @@ impl ::protobuf::Message for ProgramMessage {
                 &ProgramMessage_oneof_data::ACCEPT_RES(v) => {
                     os.write_uint32(12, v)?;
                 },
+                &ProgramMessage_oneof_data::DEFAULT_RES(ref v) => {
+                    os.write_tag(13, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+                    os.write_raw_varint32(v.get_cached_size())?;
+                    v.write_to_with_cached_sizes(os)?;
+                },
+                &ProgramMessage_oneof_data::WITHDRAW_REQ(v) => {
+                    os.write_uint32(14, v)?;
+                },
+                &ProgramMessage_oneof_data::EXIT(ref v) => {
+                    os.write_tag(15, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+                    os.write_raw_varint32(v.get_cached_size())?;
+                    v.write_to_with_cached_sizes(os)?;
+                },
             };
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
@@ -5829,6 +6204,21 @@ impl ::protobuf::Message for ProgramMessage {
                 ProgramMessage::has_ACCEPT_RES,
                 ProgramMessage::get_ACCEPT_RES,
             ));
+            fields.push(::protobuf::reflect::accessor::make_singular_message_accessor::<_, ConsoleResponse>(
+                "DEFAULT_RES",
+                ProgramMessage::has_DEFAULT_RES,
+                ProgramMessage::get_DEFAULT_RES,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_u32_accessor::<_>(
+                "WITHDRAW_REQ",
+                ProgramMessage::has_WITHDRAW_REQ,
+                ProgramMessage::get_WITHDRAW_REQ,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_message_accessor::<_, ProgramExit>(
+                "EXIT",
+                ProgramMessage::has_EXIT,
+                ProgramMessage::get_EXIT,
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<ProgramMessage>(
                 "ProgramMessage",
                 fields,
@@ -5845,6 +6235,9 @@ impl ::protobuf::Message for ProgramMessage {
 
 impl ::protobuf::Clear for ProgramMessage {
     fn clear(&mut self) {
+        self.data = ::std::option::Option::None;
+        self.data = ::std::option::Option::None;
+        self.data = ::std::option::Option::None;
         self.data = ::std::option::Option::None;
         self.data = ::std::option::Option::None;
         self.data = ::std::option::Option::None;
@@ -6038,13 +6431,17 @@ static file_descriptor_proto_data: &'static [u8] = b"\
     a\"\x88\x01\n\x0fProgramResponse\x12@\n\x0cOK_GET_STATE\x18\x0c\x20\x01(\
     \x0b2\x1c.qni.api.ProgramCommandArrayH\0R\nOKGETSTATE\x12+\n\x03ERR\x18\
     \xff\x01\x20\x01(\x0b2\x16.qni.api.ErrorResponseH\0R\x03ERRB\x06\n\x04da\
-    ta\"\x94\x01\n\x0eProgramMessage\x12+\n\x03REQ\x18\n\x20\x01(\x0b2\x17.q\
-    ni.api.ProgramRequestH\0R\x03REQ\x12,\n\x03RES\x18\x0b\x20\x01(\x0b2\x18\
-    .qni.api.ProgramResponseH\0R\x03RES\x12\x1f\n\nACCEPT_RES\x18\x0c\x20\
-    \x01(\rH\0R\tACCEPTRESB\x06\n\x04data*.\n\tFontStyle\x12\x0b\n\x07REGULA\
-    R\x10\0\x12\n\n\x06ITALIC\x10\x01\x12\x08\n\x04BOLD\x10\x02*,\n\tTextAli\
-    gn\x12\x08\n\x04LEFT\x10\0\x12\t\n\x05RIGHT\x10\x01\x12\n\n\x06CENTER\
-    \x10\x02b\x06proto3\
+    ta\"9\n\x0bProgramExit\x12\x12\n\x04code\x18\x01\x20\x01(\x05R\x04code\
+    \x12\x16\n\x06reason\x18\x02\x20\x01(\tR\x06reason\"\xa2\x02\n\x0eProgra\
+    mMessage\x12+\n\x03REQ\x18\n\x20\x01(\x0b2\x17.qni.api.ProgramRequestH\0\
+    R\x03REQ\x12,\n\x03RES\x18\x0b\x20\x01(\x0b2\x18.qni.api.ProgramResponse\
+    H\0R\x03RES\x12\x1f\n\nACCEPT_RES\x18\x0c\x20\x01(\rH\0R\tACCEPTRES\x12;\
+    \n\x0bDEFAULT_RES\x18\r\x20\x01(\x0b2\x18.qni.api.ConsoleResponseH\0R\nD\
+    EFAULTRES\x12#\n\x0cWITHDRAW_REQ\x18\x0e\x20\x01(\rH\0R\x0bWITHDRAWREQ\
+    \x12*\n\x04EXIT\x18\x0f\x20\x01(\x0b2\x14.qni.api.ProgramExitH\0R\x04EXI\
+    TB\x06\n\x04data*.\n\tFontStyle\x12\x0b\n\x07REGULAR\x10\0\x12\n\n\x06IT\
+    ALIC\x10\x01\x12\x08\n\x04BOLD\x10\x02*,\n\tTextAlign\x12\x08\n\x04LEFT\
+    \x10\0\x12\t\n\x05RIGHT\x10\x01\x12\n\n\x06CENTER\x10\x02b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
@@ -0,0 +1,411 @@
+use std::any::Any;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, ManualClock};
+use crate::console::ConsoleContext;
+use crate::format::{self, read_header, read_prefixed, write_header, write_prefixed, FormatError};
+use crate::protos::qni_api::*;
+
+const MAGIC: &[u8; 4] = b"QNIR";
+const VERSION: u32 = 1;
+
+const KIND_COMMAND: u8 = 1;
+const KIND_REQUEST: u8 = 2;
+const KIND_RESPONSE: u8 = 3;
+
+/// Recording save or load error
+pub type RecordError = FormatError;
+
+/// Recorded event
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordEvent {
+    /// Command appended
+    Command(ProgramCommand),
+    /// Request issued through `wait_console`
+    Request(ProgramRequest),
+    /// Response delivered to program
+    Response(ConsoleResponse),
+}
+
+/// Recorded event with elapsed time from start of recording
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordEntry {
+    pub elapsed: Duration,
+    pub event: RecordEvent,
+}
+
+/// Recorded session
+///
+/// # Format
+///
+/// Starts with `QNIR` and little endian u32 version, then each entry is
+///
+/// | Size | Content                                           |
+/// |------|---------------------------------------------------|
+/// | 1    | Kind, `1` command, `2` request, `3` response      |
+/// | 8    | Elapsed microseconds, little endian u64           |
+/// | 4+N  | Length prefixed protobuf message                  |
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Recording {
+    pub entries: Vec<RecordEntry>,
+}
+
+impl Recording {
+    /// Recorded commands in order
+    pub fn commands(&self) -> impl Iterator<Item = &ProgramCommand> {
+        self.entries.iter().filter_map(|entry| match entry.event {
+            RecordEvent::Command(ref command) => Some(command),
+            _ => None,
+        })
+    }
+
+    /// Recorded requests in order
+    pub fn requests(&self) -> impl Iterator<Item = &ProgramRequest> {
+        self.entries.iter().filter_map(|entry| match entry.event {
+            RecordEvent::Request(ref req) => Some(req),
+            _ => None,
+        })
+    }
+
+    /// Find response of request tag
+    pub fn response(&self, tag: u32) -> Option<&ConsoleResponse> {
+        self.response_entry(tag).map(|(_, res)| res)
+    }
+
+    /// Find response of request tag with its elapsed time
+    fn response_entry(&self, tag: u32) -> Option<(Duration, &ConsoleResponse)> {
+        self.entries.iter().find_map(|entry| match entry.event {
+            RecordEvent::Response(ref res) if res.get_tag() == tag => Some((entry.elapsed, res)),
+            _ => None,
+        })
+    }
+
+    /// Write recording
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), RecordError> {
+        write_header(writer, MAGIC, VERSION)?;
+
+        for entry in &self.entries {
+            let kind = match entry.event {
+                RecordEvent::Command(_) => KIND_COMMAND,
+                RecordEvent::Request(_) => KIND_REQUEST,
+                RecordEvent::Response(_) => KIND_RESPONSE,
+            };
+
+            writer.write_all(&[kind])?;
+            writer.write_all(&(entry.elapsed.as_micros() as u64).to_le_bytes())?;
+
+            match entry.event {
+                RecordEvent::Command(ref command) => write_prefixed(writer, command)?,
+                RecordEvent::Request(ref req) => write_prefixed(writer, req)?,
+                RecordEvent::Response(ref res) => write_prefixed(writer, res)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read recording
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, RecordError> {
+        read_header(reader, MAGIC, VERSION)?;
+
+        let mut entries = Vec::new();
+
+        loop {
+            let mut kind = [0];
+
+            if reader.read(&mut kind)? == 0 {
+                break;
+            }
+
+            let mut buf = [0; 8];
+            reader.read_exact(&mut buf)?;
+            let elapsed = Duration::from_micros(u64::from_le_bytes(buf));
+
+            let event = match kind[0] {
+                KIND_COMMAND => RecordEvent::Command(read_prefixed(reader)?),
+                KIND_REQUEST => RecordEvent::Request(read_prefixed(reader)?),
+                KIND_RESPONSE => RecordEvent::Response(read_prefixed(reader)?),
+                kind => return Err(RecordError::InvalidEntry(kind)),
+            };
+
+            entries.push(RecordEntry { elapsed, event });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Save recording to file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordError> {
+        format::save(path, |writer| self.write_to(writer))
+    }
+
+    /// Load recording from file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordError> {
+        format::load(path, Self::read_from)
+    }
+}
+
+/// Record events of ConsoleContext
+///
+/// Attach it with `ConsoleContext::set_recorder`
+pub struct Recorder {
+    start: Instant,
+    recording: Mutex<Recording>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Create new Recorder, elapsed time is measured from now
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            recording: Mutex::new(Recording::default()),
+        }
+    }
+
+    pub(crate) fn record(&self, event: RecordEvent) {
+        let elapsed = self.start.elapsed();
+        self.recording
+            .lock()
+            .unwrap()
+            .entries
+            .push(RecordEntry { elapsed, event });
+    }
+
+    /// Get recorded session so far
+    pub fn recording(&self) -> Recording {
+        self.recording.lock().unwrap().clone()
+    }
+}
+
+/// Difference between recording and replay
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayDiff {
+    /// Command at index is different
+    Command {
+        index: usize,
+        expected: Option<ProgramCommand>,
+        actual: Option<ProgramCommand>,
+    },
+    /// Request is different or not issued
+    Request {
+        tag: u32,
+        expected: Option<ProgramRequest>,
+        actual: Option<ProgramRequest>,
+    },
+}
+
+impl fmt::Display for ReplayDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayDiff::Command {
+                index,
+                expected,
+                actual,
+            } => write!(f, "command #{}\n- {:?}\n+ {:?}", index, expected, actual),
+            ReplayDiff::Request {
+                tag,
+                expected,
+                actual,
+            } => write!(f, "request #{}\n- {:?}\n+ {:?}", tag, expected, actual),
+        }
+    }
+}
+
+/// Result of replay
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    pub diffs: Vec<ReplayDiff>,
+    /// Panic message of program, panic is a divergence
+    pub panicked: Option<String>,
+}
+
+impl ReplayReport {
+    /// Replay produced same commands and requests without panic
+    pub fn is_match(&self) -> bool {
+        self.diffs.is_empty() && self.panicked.is_none()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_match() {
+            return write!(f, "replay matched");
+        }
+
+        if let Some(message) = &self.panicked {
+            writeln!(f, "program panicked: {}", message)?;
+        }
+
+        writeln!(f, "replay has {} differences", self.diffs.len())?;
+
+        for diff in &self.diffs {
+            writeln!(f, "{}", diff)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Feed recorded responses to program and compare its output with recording
+pub struct Replayer {
+    recording: Recording,
+    request_timeout: Duration,
+    clock: Arc<ManualClock>,
+}
+
+impl Replayer {
+    /// Create new Replayer
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            request_timeout: Duration::from_secs(10),
+            clock: Arc::new(ManualClock::new()),
+        }
+    }
+
+    /// Set clock of replayed console
+    ///
+    /// Clock is advanced to elapsed time of each recorded response before it is delivered,
+    /// so timeouts of program expire as they did in recording.
+    pub fn clock(mut self, clock: Arc<ManualClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set max time to wait each request from program
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Run program on new ConsoleContext and replay recorded responses
+    ///
+    /// Requests are matched in order, so recording doesn't need to start from tag `0`.
+    /// They are compared without `tag` and `expire` because those depend on console state.
+    pub fn replay<F>(&self, program: F) -> ReplayReport
+    where
+        F: FnOnce(Arc<ConsoleContext>) + Send + 'static,
+    {
+        let start = self.clock.now();
+        let ctx = Arc::new(ConsoleContext::with_clock(self.clock.clone()));
+        let handle = {
+            let ctx = ctx.clone();
+            thread::spawn(move || program(ctx))
+        };
+
+        let mut report = ReplayReport::default();
+        let mut next_tag = 0;
+
+        for expected in self.recording.requests() {
+            let tag = expected.get_tag();
+            let actual = self.wait_request(&ctx, &handle, next_tag);
+
+            if actual.as_ref().map(normalize) != Some(normalize(expected)) {
+                report.diffs.push(ReplayDiff::Request {
+                    tag,
+                    expected: Some(expected.clone()),
+                    actual: actual.clone(),
+                });
+            }
+
+            let actual_tag = match actual {
+                Some(req) => req.get_tag(),
+                None => break,
+            };
+            next_tag = actual_tag + 1;
+
+            if let Some((elapsed, res)) = self.recording.response_entry(tag) {
+                let passed = self.clock.now() - start;
+                self.clock.advance(elapsed.saturating_sub(passed));
+
+                let mut res = res.clone();
+                res.set_tag(actual_tag);
+                ctx.on_recv_response(res);
+            }
+        }
+
+        let deadline = Instant::now() + self.request_timeout;
+
+        while !handle.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        ctx.set_exit();
+        if let Err(payload) = handle.join() {
+            report.panicked = Some(panic_message(payload.as_ref()));
+        }
+
+        let expected: Vec<_> = self.recording.commands().collect();
+        let actual = ctx.export_command(0);
+
+        for index in 0..expected.len().max(actual.len()) {
+            let expected = expected.get(index).copied();
+            let actual = actual.get(index);
+
+            if expected != actual {
+                report.diffs.push(ReplayDiff::Command {
+                    index,
+                    expected: expected.cloned(),
+                    actual: actual.cloned(),
+                });
+            }
+        }
+
+        report
+    }
+
+    fn wait_request(
+        &self,
+        ctx: &ConsoleContext,
+        handle: &thread::JoinHandle<()>,
+        tag: u32,
+    ) -> Option<ProgramRequest> {
+        let deadline = Instant::now() + self.request_timeout;
+
+        loop {
+            if let Some(req) = ctx.try_get_req() {
+                if req.get_tag() >= tag {
+                    return Some(req);
+                }
+            }
+
+            if handle.is_finished() || Instant::now() >= deadline {
+                return None;
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".into()
+    }
+}
+
+fn normalize(req: &ProgramRequest) -> ProgramRequest {
+    let mut req = req.clone();
+    req.clear_tag();
+
+    if req.has_INPUT() {
+        req.mut_INPUT().clear_expire();
+    }
+
+    req
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+use crate::console::ConsoleContext;
+
+/// Bytes of random generated session ID
+const SESSION_ID_BYTES: usize = 16;
+
+/// Console registry error
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    /// Session ID is already used
+    #[error("session {0} already exists")]
+    DuplicatedId(String),
+}
+
+/// Consoles of many programs hosted in one process by session ID
+///
+/// Console is idle when `need_exit()` is true and it has no connector,
+/// call `reap` periodically to remove them.
+#[derive(Default)]
+pub struct ConsoleRegistry {
+    consoles: RwLock<HashMap<String, Arc<ConsoleContext>>>,
+}
+
+impl ConsoleRegistry {
+    /// Create new ConsoleRegistry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create new console with generated session ID
+    ///
+    /// ID is 128 bit random hex from OS random source, so it can't be guessed
+    ///
+    /// # Panics
+    ///
+    /// OS random source is not available
+    pub fn create(&self) -> (String, Arc<ConsoleContext>) {
+        let ctx = Arc::new(ConsoleContext::new());
+        let mut consoles = self.consoles.write().unwrap();
+
+        loop {
+            let id = random_id();
+
+            if !consoles.contains_key(&id) {
+                consoles.insert(id.clone(), ctx.clone());
+                break (id, ctx);
+            }
+        }
+    }
+
+    /// Register console with session ID
+    ///
+    /// # Errors
+    ///
+    /// Session ID is already used
+    pub fn insert(
+        &self,
+        id: impl Into<String>,
+        ctx: Arc<ConsoleContext>,
+    ) -> Result<(), RegistryError> {
+        let id = id.into();
+        let mut consoles = self.consoles.write().unwrap();
+
+        if consoles.contains_key(&id) {
+            return Err(RegistryError::DuplicatedId(id));
+        }
+
+        consoles.insert(id, ctx);
+
+        Ok(())
+    }
+
+    /// Find console by session ID
+    pub fn get(&self, id: &str) -> Option<Arc<ConsoleContext>> {
+        self.consoles.read().unwrap().get(id).cloned()
+    }
+
+    /// Sorted session IDs
+    pub fn list(&self) -> Vec<String> {
+        let mut ids: Vec<_> = self.consoles.read().unwrap().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Get count of consoles
+    pub fn len(&self) -> usize {
+        self.consoles.read().unwrap().len()
+    }
+
+    /// Registry has no console
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove console by session ID
+    pub fn remove(&self, id: &str) -> Option<Arc<ConsoleContext>> {
+        self.consoles.write().unwrap().remove(id)
+    }
+
+    /// Remove idle consoles and return their session IDs
+    pub fn reap(&self) -> Vec<String> {
+        let mut reaped = Vec::new();
+
+        self.consoles.write().unwrap().retain(|id, ctx| {
+            let idle = ctx.need_exit() && ctx.connector_count() == 0;
+
+            if idle {
+                reaped.push(id.clone());
+            }
+
+            !idle
+        });
+
+        reaped.sort();
+        reaped
+    }
+}
+
+fn random_id() -> String {
+    let mut bytes = [0u8; SESSION_ID_BYTES];
+    getrandom::getrandom(&mut bytes).expect("OS random source");
+
+    let mut id = String::with_capacity(SESSION_ID_BYTES * 2);
+
+    for byte in bytes {
+        write!(id, "{:02x}", byte).unwrap();
+    }
+
+    id
+}
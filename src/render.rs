@@ -0,0 +1,143 @@
+use std::fmt::Write;
+
+use crate::color::Color;
+use crate::console::ConsoleContext;
+use crate::font::FontStyles;
+use crate::layout::display_width;
+use crate::protos::qni_api::*;
+use crate::screen::{Line, ScreenState, Span};
+
+/// Output format of TextRenderer
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RenderMode {
+    /// Text only, for logs
+    Plain,
+    /// Text with ANSI escape sequences, for terminals
+    Ansi,
+}
+
+/// Render console output as text
+///
+/// Colors are treated as `0xAARRGGBB` and alpha is ignored.
+/// Lines are aligned within `width` columns, `0` disables alignment and rules are empty.
+#[derive(Copy, Clone, Debug)]
+pub struct TextRenderer {
+    mode: RenderMode,
+    width: usize,
+}
+
+impl TextRenderer {
+    /// Create new TextRenderer
+    pub fn new(mode: RenderMode, width: usize) -> Self {
+        Self { mode, width }
+    }
+
+    /// Create plain text renderer
+    pub fn plain(width: usize) -> Self {
+        Self::new(RenderMode::Plain, width)
+    }
+
+    /// Create ANSI terminal renderer
+    pub fn ansi(width: usize) -> Self {
+        Self::new(RenderMode::Ansi, width)
+    }
+
+    /// Render all commands of console
+    pub fn render_console(&self, ctx: &ConsoleContext) -> String {
+        self.render_screen(&ScreenState::from_commands(&ctx.command_snapshot(0)))
+    }
+
+    /// Render commands
+    pub fn render_commands(&self, commands: &[ProgramCommand]) -> String {
+        self.render_screen(&ScreenState::from_commands(commands))
+    }
+
+    /// Render screen, each line ends with `'\n'`
+    pub fn render_screen(&self, screen: &ScreenState) -> String {
+        let mut ret = String::new();
+
+        for line in screen.iter_lines() {
+            ret.push_str(&self.render_line(line));
+            ret.push('\n');
+        }
+
+        ret
+    }
+
+    /// Render a line without line ending
+    pub fn render_line(&self, line: &Line) -> String {
+        if line.rule {
+            let rule = match self.mode {
+                RenderMode::Plain => "-",
+                RenderMode::Ansi => "─",
+            };
+            return rule.repeat(self.width);
+        }
+
+        let mut ret = String::new();
+
+        let text_width: usize = line
+            .spans
+            .iter()
+            .map(|span| display_width(&span.text))
+            .sum();
+        let padding = self.width.saturating_sub(text_width);
+
+        let indent = match line.align() {
+            TextAlign::LEFT => 0,
+            TextAlign::RIGHT => padding,
+            TextAlign::CENTER => padding / 2,
+        };
+
+        ret.push_str(&" ".repeat(indent));
+
+        for span in &line.spans {
+            self.render_span(&mut ret, span);
+        }
+
+        ret
+    }
+
+    fn render_span(&self, out: &mut String, span: &Span) {
+        if self.mode == RenderMode::Plain {
+            out.push_str(&span.text);
+            return;
+        }
+
+        let mut sgr = String::new();
+
+        let styles = span.settings.font_styles();
+
+        if styles.contains(FontStyles::BOLD) {
+            sgr.push_str(";1");
+        }
+
+        if styles.contains(FontStyles::ITALIC) {
+            sgr.push_str(";3");
+        }
+
+        if span.is_button() || styles.contains(FontStyles::UNDERLINE) {
+            sgr.push_str(";4");
+        }
+
+        if styles.contains(FontStyles::STRIKETHROUGH) {
+            sgr.push_str(";9");
+        }
+
+        if let Some(color) = span.settings.text_color {
+            let color = Color(color);
+            write!(sgr, ";38;2;{};{};{}", color.r(), color.g(), color.b()).unwrap();
+        }
+
+        if let Some(color) = span.settings.back_color {
+            let color = Color(color);
+            write!(sgr, ";48;2;{};{};{}", color.r(), color.g(), color.b()).unwrap();
+        }
+
+        if sgr.is_empty() {
+            out.push_str(&span.text);
+        } else {
+            write!(out, "\x1b[{}m{}\x1b[0m", &sgr[1..], span.text).unwrap();
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::color::Color;
+use crate::font::FontStyles;
+use crate::protos::qni_api::*;
+
+/// Effective console settings
+///
+/// `None` means the setting was never updated, so the frontend default is used
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsoleSettings {
+    pub text_color: Option<u32>,
+    pub back_color: Option<u32>,
+    pub highlight_color: Option<u32>,
+    pub font: Option<Font>,
+    pub text_align: TextAlign,
+}
+
+impl Default for ConsoleSettings {
+    fn default() -> Self {
+        Self {
+            text_color: None,
+            back_color: None,
+            highlight_color: None,
+            font: None,
+            text_align: TextAlign::LEFT,
+        }
+    }
+}
+
+impl ConsoleSettings {
+    /// Apply UPDATE_SETTING item
+    pub fn apply(&mut self, item: &ConsoleSettingItem) {
+        match item.data {
+            Some(ConsoleSettingItem_oneof_data::TEXT_COLOR(color)) => self.text_color = Some(color),
+            Some(ConsoleSettingItem_oneof_data::BACK_COLOR(color)) => self.back_color = Some(color),
+            Some(ConsoleSettingItem_oneof_data::HIGHLIGHT_COLOR(color)) => {
+                self.highlight_color = Some(color)
+            }
+            Some(ConsoleSettingItem_oneof_data::FONT(ref font)) => self.font = Some(font.clone()),
+            Some(ConsoleSettingItem_oneof_data::TEXT_ALIGN(align)) => self.text_align = align,
+            None => {}
+        }
+    }
+
+    /// UPDATE_SETTING commands which change these settings to target
+    ///
+    /// Color which is `None` in target has no value to send and is kept as is,
+    /// font which is `None` in target is sent as empty font which uses frontend default
+    pub fn update_commands(&self, target: &ConsoleSettings) -> Vec<ProgramCommand> {
+        let mut commands = Vec::new();
+
+        if let Some(color) = target.text_color.filter(|&c| self.text_color != Some(c)) {
+            commands.push(ProgramCommand::text_color(Color(color)));
+        }
+
+        if let Some(color) = target.back_color.filter(|&c| self.back_color != Some(c)) {
+            commands.push(ProgramCommand::back_color(Color(color)));
+        }
+
+        if let Some(color) = target
+            .highlight_color
+            .filter(|&c| self.highlight_color != Some(c))
+        {
+            commands.push(ProgramCommand::highlight_color(Color(color)));
+        }
+
+        let font = target.font.clone().unwrap_or_default();
+
+        if self.font.clone().unwrap_or_default() != font {
+            commands.push(ProgramCommand::font(font));
+        }
+
+        if self.text_align != target.text_align {
+            commands.push(ProgramCommand::text_align(target.text_align));
+        }
+
+        commands
+    }
+
+    /// Styles of current font, empty if font is never set
+    pub fn font_styles(&self) -> FontStyles {
+        self.font.as_ref().map_or(FontStyles::empty(), Font::styles)
+    }
+}
+
+/// Styled piece of text in a line
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub settings: ConsoleSettings,
+    /// Value sent back when this span is a button
+    pub button: Option<InputResponse>,
+}
+
+impl Span {
+    /// Is this span a button
+    #[inline]
+    pub fn is_button(&self) -> bool {
+        self.button.is_some()
+    }
+}
+
+/// Rendered line
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Line {
+    pub spans: Vec<Span>,
+    /// Line is a horizontal rule from DRAW_LINE
+    pub rule: bool,
+}
+
+impl Line {
+    /// Line has no span and is not a rule
+    pub fn is_empty(&self) -> bool {
+        !self.rule && self.spans.is_empty()
+    }
+
+    /// Line text without styles
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Alignment of line, decided by its first span
+    pub fn align(&self) -> TextAlign {
+        self.spans
+            .first()
+            .map(|span| span.settings.text_align)
+            .unwrap_or(TextAlign::LEFT)
+    }
+
+    fn push_span(&mut self, text: &str, settings: &ConsoleSettings, button: Option<InputResponse>) {
+        if text.is_empty() && button.is_none() {
+            return;
+        }
+
+        if button.is_none() {
+            if let Some(last) = self.spans.last_mut() {
+                if !last.is_button() && last.settings == *settings {
+                    last.text.push_str(text);
+                    return;
+                }
+            }
+        }
+
+        self.spans.push(Span {
+            text: text.into(),
+            settings: settings.clone(),
+            button,
+        });
+    }
+}
+
+/// Virtual screen built by applying ProgramCommands
+///
+/// This is the canonical interpretation of the command log.
+/// `lines` are completed lines and `current_line` is the line being printed.
+///
+/// * PRINT appends text to current line, `'\n'` in text completes line
+/// * PRINT_LINE and NEW_LINE complete current line
+/// * DRAW_LINE completes current line if it isn't empty and append a rule line
+/// * DELETE_LINE removes completed lines from bottom, current line is kept
+/// * CLEAR_LINE removes every line
+#[derive(Clone, Debug, Default)]
+pub struct ScreenState {
+    lines: Vec<Line>,
+    current_line: Line,
+    settings: ConsoleSettings,
+    command_count: usize,
+}
+
+impl ScreenState {
+    /// Create new empty ScreenState
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create ScreenState from commands
+    pub fn from_commands<'a>(commands: impl IntoIterator<Item = &'a ProgramCommand>) -> Self {
+        let mut state = Self::new();
+        state.apply_all(commands);
+        state
+    }
+
+    /// Completed lines
+    #[inline]
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Line which is not completed yet
+    #[inline]
+    pub fn current_line(&self) -> &Line {
+        &self.current_line
+    }
+
+    /// Current effective settings
+    #[inline]
+    pub fn settings(&self) -> &ConsoleSettings {
+        &self.settings
+    }
+
+    /// Count of applied commands, can be used as next GET_STATE offset
+    #[inline]
+    pub fn command_count(&self) -> usize {
+        self.command_count
+    }
+
+    /// Iterate completed lines and current line if it is not empty
+    pub fn iter_lines(&self) -> impl Iterator<Item = &Line> {
+        let current = if self.current_line.is_empty() {
+            None
+        } else {
+            Some(&self.current_line)
+        };
+
+        self.lines.iter().chain(current)
+    }
+
+    /// Screen text without styles, each line ends with `'\n'` except unfinished line
+    pub fn text(&self) -> String {
+        let mut ret = String::new();
+
+        for line in &self.lines {
+            ret.push_str(&line.text());
+            ret.push('\n');
+        }
+
+        ret.push_str(&self.current_line.text());
+
+        ret
+    }
+
+    /// Apply commands in order
+    pub fn apply_all<'a>(&mut self, commands: impl IntoIterator<Item = &'a ProgramCommand>) {
+        for command in commands {
+            self.apply(command);
+        }
+    }
+
+    /// Apply a command
+    pub fn apply(&mut self, command: &ProgramCommand) {
+        self.command_count += 1;
+
+        match command.data {
+            Some(ProgramCommand_oneof_data::PRINT(ref print)) => self.apply_print(print),
+            Some(ProgramCommand_oneof_data::UPDATE_SETTING(ref item)) => self.settings.apply(item),
+            None => {}
+        }
+    }
+
+    fn apply_print(&mut self, print: &ConsolePrintData) {
+        match print.data {
+            Some(ConsolePrintData_oneof_data::PRINT(ref text)) => self.print_text(text),
+            Some(ConsolePrintData_oneof_data::PRINT_LINE(ref text)) => {
+                self.print_text(text);
+                self.new_line();
+            }
+            Some(ConsolePrintData_oneof_data::PRINT_BUTTON(ref button)) => {
+                self.current_line.push_span(
+                    button.get_text(),
+                    &self.settings,
+                    Some(button.get_value().clone()),
+                );
+            }
+            Some(ConsolePrintData_oneof_data::NEW_LINE(_)) => self.new_line(),
+            Some(ConsolePrintData_oneof_data::DRAW_LINE(_)) => {
+                if !self.current_line.is_empty() {
+                    self.new_line();
+                }
+
+                self.lines.push(Line {
+                    spans: Vec::new(),
+                    rule: true,
+                });
+            }
+            Some(ConsolePrintData_oneof_data::DELETE_LINE(count)) => {
+                let len = self.lines.len().saturating_sub(count as usize);
+                self.lines.truncate(len);
+            }
+            Some(ConsolePrintData_oneof_data::CLEAR_LINE(_)) => {
+                self.lines.clear();
+                self.current_line = Line::default();
+            }
+            None => {}
+        }
+    }
+
+    fn print_text(&mut self, text: &str) {
+        let mut parts = text.split('\n');
+
+        if let Some(first) = parts.next() {
+            self.current_line.push_span(first, &self.settings, None);
+        }
+
+        for part in parts {
+            self.new_line();
+            self.current_line.push_span(part, &self.settings, None);
+        }
+    }
+
+    fn new_line(&mut self) {
+        let line = std::mem::take(&mut self.current_line);
+        self.lines.push(line);
+    }
+}
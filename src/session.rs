@@ -0,0 +1,125 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use protobuf::{Message, RepeatedField};
+
+use crate::format::{self, read_header, read_prefixed, write_header, write_prefixed};
+use crate::protos::qni_api::*;
+
+pub use crate::format::FormatError;
+
+const MAGIC: &[u8; 4] = b"QNIS";
+const VERSION: u32 = 2;
+
+const FLAG_EXIT: u8 = 0b01;
+const FLAG_REQUEST: u8 = 0b10;
+const FLAG_EXIT_STATUS: u8 = 0b100;
+
+/// Session save or load error
+pub type SessionError = FormatError;
+
+/// Saved state of ConsoleContext
+///
+/// # Format
+///
+/// | Size | Content                                                         |
+/// |------|-----------------------------------------------------------------|
+/// | 4    | `QNIS`                                                          |
+/// | 4    | Version, little endian u32                                      |
+/// | 1    | Flags, `0b01` exit, `0b10` has request, `0b100` has exit status |
+/// | 8    | Request tag, little endian u64                                  |
+/// | 4+N  | Pending ProgramRequest if flag is set                           |
+/// | 4+N  | ProgramExit if flag is set, since version 2                     |
+/// | rest | ProgramCommandArray                                             |
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConsoleSnapshot {
+    pub commands: Vec<ProgramCommand>,
+    pub request_tag: usize,
+    pub request: Option<ProgramRequest>,
+    pub exit: bool,
+    pub exit_status: Option<ProgramExit>,
+}
+
+impl ConsoleSnapshot {
+    /// Write snapshot
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), SessionError> {
+        let mut flags = 0;
+
+        if self.exit {
+            flags |= FLAG_EXIT;
+        }
+
+        if self.request.is_some() {
+            flags |= FLAG_REQUEST;
+        }
+
+        if self.exit_status.is_some() {
+            flags |= FLAG_EXIT_STATUS;
+        }
+
+        write_header(writer, MAGIC, VERSION)?;
+        writer.write_all(&[flags])?;
+        writer.write_all(&(self.request_tag as u64).to_le_bytes())?;
+
+        if let Some(request) = &self.request {
+            write_prefixed(writer, request)?;
+        }
+
+        if let Some(status) = &self.exit_status {
+            write_prefixed(writer, status)?;
+        }
+
+        let mut commands = ProgramCommandArray::new();
+        commands.set_commands(RepeatedField::from_slice(&self.commands));
+        commands.write_to_writer(writer)?;
+
+        Ok(())
+    }
+
+    /// Read snapshot
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, SessionError> {
+        read_header(reader, MAGIC, VERSION)?;
+
+        let mut flags = [0];
+        reader.read_exact(&mut flags)?;
+        let flags = flags[0];
+
+        let mut buf = [0; 8];
+        reader.read_exact(&mut buf)?;
+        let request_tag = u64::from_le_bytes(buf) as usize;
+
+        let request = if flags & FLAG_REQUEST != 0 {
+            Some(read_prefixed(reader)?)
+        } else {
+            None
+        };
+
+        let exit_status = if flags & FLAG_EXIT_STATUS != 0 {
+            Some(read_prefixed(reader)?)
+        } else {
+            None
+        };
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let commands = ProgramCommandArray::parse_from_bytes(&bytes)?;
+
+        Ok(Self {
+            commands: commands.commands.into_vec(),
+            request_tag,
+            request,
+            exit: flags & FLAG_EXIT != 0,
+            exit_status,
+        })
+    }
+
+    /// Save snapshot to file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SessionError> {
+        format::save(path, |writer| self.write_to(writer))
+    }
+
+    /// Load snapshot from file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        format::load(path, Self::read_from)
+    }
+}
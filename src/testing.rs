@@ -0,0 +1,511 @@
+//! Test harness for qni programs
+//!
+//! ```no_run
+//! use qni_core_rs::testing::MockFrontend;
+//! # fn game(_: std::sync::Arc<qni_core_rs::console::ConsoleContext>) {}
+//!
+//! let mut frontend = MockFrontend::run(game);
+//! frontend.assert_contains("Choose number");
+//! frontend.expect_int().answer_int(100);
+//! frontend.finish();
+//! frontend.assert_contains("You chose 100");
+//! frontend.assert_snapshot("choose_number");
+//! ```
+//!
+//! Snapshots are stored in `tests/snapshots/<name>.snap` of the crate under test,
+//! run tests with `QNI_UPDATE_SNAPSHOTS=1` to write them.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::clock::ManualClock;
+use crate::console::ConsoleContext;
+use crate::font::FontStyles;
+use crate::html::{button_value, css_color};
+use crate::input::input_kind;
+use crate::protos::qni_api::*;
+use crate::screen::{ScreenState, Span};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Set this env var to non empty value to write snapshots instead of comparing them
+pub const UPDATE_SNAPSHOTS_ENV: &str = "QNI_UPDATE_SNAPSHOTS";
+
+/// Scripted frontend for testing programs
+///
+/// Every expectation waits until program issues request or finishes,
+/// so screen assertions made after it see stable output.
+/// Program finished before expected request fails immediately,
+/// `timeout` only guards against hanged program.
+pub struct MockFrontend {
+    ctx: Arc<ConsoleContext>,
+    handle: Option<JoinHandle<()>>,
+    clock: Option<Arc<ManualClock>>,
+    timeout: Duration,
+    next_tag: u32,
+}
+
+impl MockFrontend {
+    /// Run program on new ConsoleContext and attach to it
+    pub fn run<F>(program: F) -> Self
+    where
+        F: FnOnce(Arc<ConsoleContext>) + Send + 'static,
+    {
+        Self::spawn(Arc::new(ConsoleContext::new()), None, program)
+    }
+
+    /// Run program on new ConsoleContext with clock which only moves by `advance`
+    pub fn run_with_clock<F>(clock: Arc<ManualClock>, program: F) -> Self
+    where
+        F: FnOnce(Arc<ConsoleContext>) + Send + 'static,
+    {
+        let ctx = Arc::new(ConsoleContext::with_clock(clock.clone()));
+        Self::spawn(ctx, Some(clock), program)
+    }
+
+    fn spawn<F>(ctx: Arc<ConsoleContext>, clock: Option<Arc<ManualClock>>, program: F) -> Self
+    where
+        F: FnOnce(Arc<ConsoleContext>) + Send + 'static,
+    {
+        let handle = {
+            let ctx = ctx.clone();
+            thread::spawn(move || program(ctx))
+        };
+
+        Self {
+            ctx,
+            handle: Some(handle),
+            clock,
+            timeout: Duration::from_secs(5),
+            next_tag: 0,
+        }
+    }
+
+    /// Attach to ConsoleContext which program is run elsewhere
+    pub fn attach(ctx: Arc<ConsoleContext>) -> Self {
+        let next_tag = ctx.get_cur_input_tag() as u32;
+
+        Self {
+            ctx,
+            handle: None,
+            clock: None,
+            timeout: Duration::from_secs(5),
+            next_tag,
+        }
+    }
+
+    /// Set max time to wait program
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get attached ConsoleContext
+    pub fn console(&self) -> &Arc<ConsoleContext> {
+        &self.ctx
+    }
+
+    /// Move clock of console forward, `timeout` of frontend still uses real time
+    ///
+    /// # Panics
+    ///
+    /// Frontend isn't run with `run_with_clock`
+    pub fn advance(&self, duration: Duration) {
+        self.clock
+            .as_ref()
+            .expect("advance needs MockFrontend::run_with_clock")
+            .advance(duration);
+    }
+
+    /// Wait next request
+    ///
+    /// # Panics
+    ///
+    /// Program finished or timeout before request
+    pub fn expect_request(&mut self) -> PendingRequest<'_> {
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            if let Some(req) = self.ctx.try_get_req() {
+                if req.get_tag() >= self.next_tag {
+                    self.next_tag = req.get_tag() + 1;
+                    return PendingRequest {
+                        frontend: self,
+                        req,
+                    };
+                }
+            }
+
+            if self.is_program_finished() {
+                self.join_program();
+                panic!(
+                    "program finished while expecting request\n\nscreen:\n{}",
+                    self.screen().text()
+                );
+            }
+
+            if Instant::now() >= deadline {
+                panic!(
+                    "no request in {:?}\n\nscreen:\n{}",
+                    self.timeout,
+                    self.screen().text()
+                );
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Wait INT request
+    pub fn expect_int(&mut self) -> PendingRequest<'_> {
+        self.expect_kind("integer")
+    }
+
+    /// Wait FLOAT request
+    pub fn expect_float(&mut self) -> PendingRequest<'_> {
+        self.expect_kind("float")
+    }
+
+    /// Wait STR request
+    pub fn expect_str(&mut self) -> PendingRequest<'_> {
+        self.expect_kind("string")
+    }
+
+    /// Wait STR_SELECT request
+    pub fn expect_select(&mut self) -> PendingRequest<'_> {
+        self.expect_kind("select")
+    }
+
+    /// Wait BOOLEAN request
+    pub fn expect_bool(&mut self) -> PendingRequest<'_> {
+        self.expect_kind("boolean")
+    }
+
+    fn expect_kind(&mut self, kind: &'static str) -> PendingRequest<'_> {
+        let pending = self.expect_request();
+        let actual = input_kind(pending.req.get_INPUT());
+
+        if actual != kind {
+            panic!(
+                "expected {} request but got {} request\n{:?}",
+                kind, actual, pending.req
+            );
+        }
+
+        pending
+    }
+
+    /// Build screen from commands printed so far
+    pub fn screen(&self) -> ScreenState {
+        ScreenState::from_commands(&self.ctx.command_snapshot(0))
+    }
+
+    /// Assert whole screen text
+    ///
+    /// # Panics
+    ///
+    /// Screen text is different, diff of lines is printed
+    pub fn assert_text(&self, expected: &str) {
+        let actual = self.screen().text();
+
+        if actual != expected {
+            panic!("screen text mismatch\n{}", diff_lines(expected, &actual));
+        }
+    }
+
+    /// Assert screen contains text
+    pub fn assert_contains(&self, text: &str) {
+        let actual = self.screen().text();
+
+        if !actual.contains(text) {
+            panic!("screen doesn't contain {:?}\n\nscreen:\n{}", text, actual);
+        }
+    }
+
+    /// Assert screen with snapshot `tests/snapshots/<name>.snap`
+    ///
+    /// # Panics
+    ///
+    /// Snapshot is different or doesn't exist, diff of lines is printed
+    pub fn assert_snapshot(&self, name: &str) {
+        let dir = env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join("tests")
+            .join("snapshots");
+
+        assert_snapshot(
+            dir.join(format!("{}.snap", name)),
+            &snapshot_text(&self.screen()),
+        );
+    }
+
+    /// Wait program finish and check it doesn't panic
+    ///
+    /// # Panics
+    ///
+    /// Program issued another request, timeout or program panicked
+    pub fn finish(&mut self) {
+        let deadline = Instant::now() + self.timeout;
+
+        while !self.is_program_finished() {
+            if let Some(req) = self.ctx.try_get_req() {
+                if req.get_tag() >= self.next_tag {
+                    panic!("unexpected request while waiting finish\n{:?}", req);
+                }
+            }
+
+            if Instant::now() >= deadline {
+                panic!("program isn't finished in {:?}", self.timeout);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        self.join_program();
+    }
+
+    fn is_program_finished(&self) -> bool {
+        match &self.handle {
+            Some(handle) => handle.is_finished(),
+            None => self.ctx.need_exit(),
+        }
+    }
+
+    fn join_program(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            if let Err(err) = handle.join() {
+                panic::resume_unwind(err);
+            }
+        }
+    }
+}
+
+impl Drop for MockFrontend {
+    fn drop(&mut self) {
+        if self.handle.is_some() {
+            self.ctx.set_exit();
+        }
+    }
+}
+
+/// Request issued by program
+pub struct PendingRequest<'a> {
+    frontend: &'a mut MockFrontend,
+    req: ProgramRequest,
+}
+
+impl PendingRequest<'_> {
+    /// Issued request
+    pub fn request(&self) -> &ProgramRequest {
+        &self.req
+    }
+
+    /// Send response
+    pub fn answer(self, value: InputResponse) {
+        let mut res = ConsoleResponse::new();
+        res.set_tag(self.req.get_tag());
+        res.set_OK_INPUT(value);
+        self.frontend.ctx.on_recv_response(res);
+    }
+
+    /// Send EMPTY response
+    pub fn answer_empty(self) {
+        let mut value = InputResponse::new();
+        value.mut_EMPTY();
+        self.answer(value);
+    }
+
+    /// Send INT response
+    pub fn answer_int(self, num: i32) {
+        let mut value = InputResponse::new();
+        value.set_INT(num);
+        self.answer(value);
+    }
+
+    /// Send FLOAT response
+    pub fn answer_float(self, num: f32) {
+        let mut value = InputResponse::new();
+        value.set_FLOAT(num);
+        self.answer(value);
+    }
+
+    /// Send STR response
+    pub fn answer_str(self, text: &str) {
+        let mut value = InputResponse::new();
+        value.set_STR(text.into());
+        self.answer(value);
+    }
+
+    /// Send BOOLEAN response
+    pub fn answer_bool(self, b: bool) {
+        let mut value = InputResponse::new();
+        value.set_BOOLEAN(b);
+        self.answer(value);
+    }
+}
+
+/// Line diff of texts, `-` is expected and `+` is actual
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.split('\n').collect();
+    let actual: Vec<_> = actual.split('\n').collect();
+
+    // longest common subsequence table
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ret = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            writeln!(ret, "  {}", expected[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(ret, "- {}", expected[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(ret, "+ {}", actual[j]).unwrap();
+            j += 1;
+        }
+    }
+
+    ret
+}
+
+/// Normalized text of screen for snapshot
+///
+/// Each line ends with `'\n'` and rules are `---`.
+/// Styled spans are wrapped like `{fg=#ff0000 bold}text{/}`,
+/// buttons like `{button=1}text{/}` and aligned lines start with `{center}` or `{right}`.
+/// Literal `{` is written as `{{`.
+pub fn snapshot_text(screen: &ScreenState) -> String {
+    let mut ret = String::new();
+
+    for line in screen.iter_lines() {
+        if line.rule {
+            ret.push_str("---\n");
+            continue;
+        }
+
+        match line.align() {
+            TextAlign::LEFT => {}
+            TextAlign::RIGHT => ret.push_str("{right}"),
+            TextAlign::CENTER => ret.push_str("{center}"),
+        }
+
+        for span in &line.spans {
+            write_snapshot_span(&mut ret, span);
+        }
+
+        ret.push('\n');
+    }
+
+    ret
+}
+
+fn write_snapshot_span(out: &mut String, span: &Span) {
+    let settings = &span.settings;
+    let mut attrs = Vec::new();
+
+    if let Some(value) = &span.button {
+        attrs.push(format!("button={}", button_value(value)));
+    }
+
+    if let Some(color) = settings.text_color {
+        attrs.push(format!("fg={}", css_color(color)));
+    }
+
+    if let Some(color) = settings.back_color {
+        attrs.push(format!("bg={}", css_color(color)));
+    }
+
+    if span.button.is_some() {
+        if let Some(color) = settings.highlight_color {
+            attrs.push(format!("hl={}", css_color(color)));
+        }
+    }
+
+    if let Some(font) = &settings.font {
+        if !font.font_family.is_empty() {
+            attrs.push(format!("font={:?}", font.font_family));
+        }
+
+        if font.font_size > 0.0 {
+            attrs.push(format!("size={}", font.font_size));
+        }
+    }
+
+    for (name, style) in [
+        ("bold", FontStyles::BOLD),
+        ("italic", FontStyles::ITALIC),
+        ("underline", FontStyles::UNDERLINE),
+        ("strike", FontStyles::STRIKETHROUGH),
+    ] {
+        if settings.font_styles().contains(style) {
+            attrs.push(name.into());
+        }
+    }
+
+    let text = span.text.replace('{', "{{");
+
+    if attrs.is_empty() {
+        out.push_str(&text);
+    } else {
+        write!(out, "{{{}}}{}{{/}}", attrs.join(" "), text).unwrap();
+    }
+}
+
+/// Compare text with snapshot file or write it when `QNI_UPDATE_SNAPSHOTS` is set
+///
+/// # Panics
+///
+/// Snapshot is different or doesn't exist
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+
+    if env::var_os(UPDATE_SNAPSHOTS_ENV).is_some_and(|value| !value.is_empty()) {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        fs::write(path, actual).unwrap();
+        return;
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(err) => panic!(
+            "can't read snapshot {}: {}\nrun with {}=1 to create it\n\nactual:\n{}",
+            path.display(),
+            err,
+            UPDATE_SNAPSHOTS_ENV,
+            actual
+        ),
+    };
+
+    if expected != actual {
+        panic!(
+            "snapshot {} mismatch, run with {}=1 to update it\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            diff_lines(&expected, actual)
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use protobuf::well_known_types::StringValue;
+use protobuf::Message;
+
+use crate::connector::ConnectorContext;
+use crate::console::ConsoleContext;
+use crate::protos::qni_api::*;
+use crate::registry::ConsoleRegistry;
+
+/// Max length of message read from peer, longer frame is rejected before allocation
+pub const MAX_MESSAGE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Max length of handshake, it is read before peer is known
+const MAX_HANDSHAKE_LEN: u64 = 1024;
+
+/// Time to wait for handshake before closing connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Write varint length delimited message
+pub fn write_message<M: Message>(writer: &mut impl Write, msg: &M) -> io::Result<()> {
+    let bytes = msg.write_length_delimited_to_bytes()?;
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
+
+/// Write already encoded message with varint length
+fn write_encoded(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    let mut os = protobuf::CodedOutputStream::new(&mut *writer);
+    os.write_raw_varint64(bytes.len() as u64)?;
+    os.write_raw_bytes(bytes)?;
+    os.flush()?;
+    drop(os);
+    writer.flush()
+}
+
+/// Read varint length delimited message
+///
+/// Return `None` when stream is closed before message.
+/// Frame longer than `MAX_MESSAGE_LEN` is `io::ErrorKind::InvalidData`
+pub fn read_message<M: Message>(reader: &mut impl Read) -> io::Result<Option<M>> {
+    read_message_limited(reader, MAX_MESSAGE_LEN)
+}
+
+fn read_message_limited<M: Message>(reader: &mut impl Read, max_len: u64) -> io::Result<Option<M>> {
+    let mut len = 0u64;
+    let mut shift = 0;
+    let mut byte = [0u8];
+
+    loop {
+        if reader.read(&mut byte)? == 0 {
+            if shift == 0 {
+                return Ok(None);
+            }
+
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        if shift >= 64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid length"));
+        }
+
+        len |= u64::from(byte[0] & 0x7F) << shift;
+        shift += 7;
+
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+
+    if len > max_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message length {} exceeds {}", len, max_len),
+        ));
+    }
+
+    let mut buf = vec![0; len as usize];
+    reader.read_exact(&mut buf)?;
+
+    Ok(Some(M::parse_from_bytes(&buf)?))
+}
+
+/// Write handshake which selects console by session ID
+pub fn write_handshake(writer: &mut impl Write, session_id: &str) -> io::Result<()> {
+    let mut msg = StringValue::new();
+    msg.set_value(session_id.into());
+    write_message(writer, &msg)
+}
+
+/// Read handshake and return session ID
+///
+/// Return `None` when stream is closed before handshake
+pub fn read_handshake(reader: &mut impl Read) -> io::Result<Option<String>> {
+    Ok(
+        read_message_limited::<StringValue>(reader, MAX_HANDSHAKE_LEN)?
+            .map(|mut msg| msg.take_value()),
+    )
+}
+
+/// Serve ConnectorContext over stream until peer close it
+pub fn serve(
+    connector: ConnectorContext,
+    mut reader: impl Read + Send + 'static,
+    writer: impl Write + Send + 'static,
+) -> io::Result<()> {
+    let connector = Arc::new(connector);
+    let writer = Arc::new(Mutex::new(writer));
+    let closed = Arc::new(AtomicBool::new(false));
+
+    let recv_handle = {
+        let connector = connector.clone();
+        let writer = writer.clone();
+        let closed = closed.clone();
+
+        thread::spawn(move || {
+            let ret = recv_loop(&connector, &mut reader, &writer);
+            closed.store(true, Ordering::Release);
+            ret
+        })
+    };
+
+    while !closed.load(Ordering::Acquire) {
+        while let Some(msg) = connector.try_get_msg() {
+            if let Err(err) = write_message(&mut *writer.lock().unwrap(), &msg) {
+                closed.store(true, Ordering::Release);
+                return Err(err);
+            }
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    recv_handle.join().unwrap()
+}
+
+fn recv_loop(
+    connector: &ConnectorContext,
+    reader: &mut impl Read,
+    writer: &Mutex<impl Write>,
+) -> io::Result<()> {
+    while let Some(msg) = read_message::<ConsoleMessage>(reader)? {
+        if let Some(res) = connector.on_recv_message_encoded(msg) {
+            write_encoded(&mut *writer.lock().unwrap(), &res)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Serve ConnectorContext over TcpStream
+pub fn serve_tcp(connector: ConnectorContext, stream: TcpStream) -> io::Result<()> {
+    let reader = stream.try_clone()?;
+    serve(connector, reader, stream)
+}
+
+/// Accept next connection
+///
+/// Error of aborted connection is skipped, other errors are returned
+fn accept(listener: &TcpListener) -> io::Result<TcpStream> {
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => return Ok(stream),
+            Err(err) => match err.kind() {
+                io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::Interrupted => continue,
+                _ => return Err(err),
+            },
+        }
+    }
+}
+
+/// Accept connections and serve console to each of them on new thread
+///
+/// Return error when accept fails with other than aborted connection
+pub fn serve_listener(listener: TcpListener, console_ctx: Arc<ConsoleContext>) -> io::Result<()> {
+    loop {
+        let stream = accept(&listener)?;
+        let connector = ConnectorContext::new(console_ctx.clone());
+
+        thread::spawn(move || serve_tcp(connector, stream));
+    }
+}
+
+/// Accept connections and serve console selected by handshake on new thread
+///
+/// Connection with unknown session ID or without handshake in time is closed.
+/// Return error when accept fails with other than aborted connection
+pub fn serve_registry(listener: TcpListener, registry: Arc<ConsoleRegistry>) -> io::Result<()> {
+    loop {
+        let mut stream = accept(&listener)?;
+        let registry = registry.clone();
+
+        thread::spawn(move || {
+            stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+
+            let console_ctx = match read_handshake(&mut stream)? {
+                Some(id) => registry.get(&id),
+                None => None,
+            };
+
+            stream.set_read_timeout(None)?;
+
+            match console_ctx {
+                Some(console_ctx) => serve_tcp(ConnectorContext::new(console_ctx), stream),
+                None => Ok(()),
+            }
+        });
+    }
+}
+
+/// Frontend side of connection to program
+pub trait FrontendTransport {
+    /// Send message to program
+    fn send(&mut self, msg: ConsoleMessage) -> io::Result<()>;
+
+    /// Receive message from program without blocking
+    ///
+    /// # Errors
+    ///
+    /// `io::ErrorKind::ConnectionAborted` is returned when connection is closed
+    fn try_recv(&mut self) -> io::Result<Option<ProgramMessage>>;
+}
+
+/// Transport for program running in same process
+pub struct LocalTransport {
+    connector: ConnectorContext,
+    received: VecDeque<ProgramMessage>,
+}
+
+impl LocalTransport {
+    /// Create new LocalTransport
+    pub fn new(console_ctx: Arc<ConsoleContext>) -> Self {
+        Self {
+            connector: ConnectorContext::new(console_ctx),
+            received: VecDeque::new(),
+        }
+    }
+}
+
+impl FrontendTransport for LocalTransport {
+    fn send(&mut self, msg: ConsoleMessage) -> io::Result<()> {
+        if let Some(res) = self.connector.on_recv_message(msg) {
+            self.received.push_back(res);
+        }
+
+        Ok(())
+    }
+
+    fn try_recv(&mut self) -> io::Result<Option<ProgramMessage>> {
+        match self.received.pop_front() {
+            Some(msg) => Ok(Some(msg)),
+            None => Ok(self.connector.try_get_msg()),
+        }
+    }
+}
+
+/// Transport for program served over TcpStream
+pub struct StreamTransport {
+    stream: TcpStream,
+    received: Receiver<io::Result<ProgramMessage>>,
+}
+
+impl StreamTransport {
+    /// Connect to program
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(TcpStream::connect(addr)?)
+    }
+
+    /// Connect to console of session served by `serve_registry`
+    pub fn connect_session(addr: impl ToSocketAddrs, session_id: &str) -> io::Result<Self> {
+        let mut stream = TcpStream::connect(addr)?;
+        write_handshake(&mut stream, session_id)?;
+        Self::new(stream)
+    }
+
+    /// Create new StreamTransport from connected stream
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        let mut reader = stream.try_clone()?;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || loop {
+            match read_message::<ProgramMessage>(&mut reader) {
+                Ok(Some(msg)) => {
+                    if tx.send(Ok(msg)).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    let _ = tx.send(Err(err));
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            stream,
+            received: rx,
+        })
+    }
+}
+
+impl FrontendTransport for StreamTransport {
+    fn send(&mut self, msg: ConsoleMessage) -> io::Result<()> {
+        write_message(&mut self.stream, &msg)
+    }
+
+    fn try_recv(&mut self) -> io::Result<Option<ProgramMessage>> {
+        match self.received.try_recv() {
+            Ok(msg) => msg.map(Some),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(io::ErrorKind::ConnectionAborted.into()),
+        }
+    }
+}
@@ -0,0 +1,602 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use chrono::prelude::*;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, queue, terminal};
+
+use crate::input::{input_kind, is_acceptable, parse_input};
+use crate::layout::display_width;
+use crate::protos::qni_api::*;
+use crate::render::TextRenderer;
+use crate::screen::{Line, ScreenState};
+use crate::transport::FrontendTransport;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const STATE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Terminal frontend
+///
+/// Print program output with ANSI colors and handle every input type.
+/// Buttons printed after last input and STR_SELECT options can be selected by
+/// arrow keys, other inputs are typed in line editor.
+pub struct TtyFrontend<T: FrontendTransport> {
+    transport: T,
+    screen: ScreenState,
+    printed_rows: Vec<usize>,
+    erase_rows: usize,
+    transient_rows: usize,
+    button_from: usize,
+    input: Option<InputState>,
+    state_requested: Option<Instant>,
+    exited: bool,
+    exit_status: Option<ProgramExit>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: FrontendTransport> TtyFrontend<T> {
+    /// Create new TtyFrontend
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            screen: ScreenState::new(),
+            printed_rows: Vec::new(),
+            erase_rows: 0,
+            transient_rows: 0,
+            button_from: 0,
+            input: None,
+            state_requested: None,
+            exited: false,
+            exit_status: None,
+            width: 80,
+            height: 24,
+        }
+    }
+
+    /// Run frontend until program exit or user press Ctrl-C
+    pub fn run(&mut self) -> io::Result<()> {
+        let _raw_mode = RawMode::enable()?;
+        let mut out = io::stdout();
+
+        loop {
+            let (width, height) = terminal::size()?;
+            self.width = width as usize;
+            self.height = height as usize;
+
+            let mut changed = self.recv_messages()?;
+
+            if self.exited {
+                self.input = None;
+                self.redraw(&mut out)?;
+                break;
+            }
+
+            if let Some(input) = &mut self.input {
+                if input.is_expired() {
+                    self.input = None;
+                    changed = true;
+                } else if input.tick() {
+                    changed = true;
+                }
+            }
+
+            if changed {
+                self.redraw(&mut out)?;
+            }
+
+            if !event::poll(POLL_INTERVAL)? {
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Release {
+                    continue;
+                }
+
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    self.input = None;
+                    self.redraw(&mut out)?;
+                    break;
+                }
+
+                let action = match &mut self.input {
+                    Some(input) => input.on_key(key),
+                    None => KeyAction::None,
+                };
+
+                match action {
+                    KeyAction::None => {}
+                    KeyAction::Redraw => self.redraw(&mut out)?,
+                    KeyAction::Submit(value) => {
+                        let input = self.input.take().unwrap();
+                        let mut msg = ConsoleMessage::new();
+                        let res = msg.mut_RES();
+                        res.set_tag(input.tag);
+                        res.set_OK_INPUT(value);
+                        self.transport.send(msg)?;
+                        self.button_from = self.screen.lines().len();
+                        self.redraw(&mut out)?;
+                    }
+                }
+            }
+        }
+
+        queue!(out, Print("\r\n"))?;
+
+        if let Some(status) = &self.exit_status {
+            if status.get_code() != 0 {
+                queue!(
+                    out,
+                    Print(format!(
+                        "program exited with code {}: {}\r\n",
+                        status.get_code(),
+                        status.get_reason()
+                    ))
+                )?;
+            } else if !status.get_reason().is_empty() {
+                queue!(out, Print(format!("{}\r\n", status.get_reason())))?;
+            }
+        }
+
+        out.flush()
+    }
+
+    fn recv_messages(&mut self) -> io::Result<bool> {
+        let state_outdated = self
+            .state_requested
+            .is_none_or(|time| time.elapsed() > STATE_TIMEOUT);
+
+        if state_outdated {
+            let mut msg = ConsoleMessage::new();
+            msg.mut_REQ()
+                .set_GET_STATE(self.screen.command_count() as u64);
+            self.transport.send(msg)?;
+            self.state_requested = Some(Instant::now());
+        }
+
+        let mut changed = false;
+
+        loop {
+            match self.transport.try_recv() {
+                Ok(Some(msg)) => changed |= self.on_message(msg),
+                Ok(None) => break,
+                Err(ref err) if err.kind() == io::ErrorKind::ConnectionAborted => {
+                    self.exited = true;
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(changed)
+    }
+
+    fn on_message(&mut self, mut msg: ProgramMessage) -> bool {
+        if msg.has_RES() {
+            let mut res = msg.take_RES();
+
+            if res.has_OK_GET_STATE() {
+                let commands = res.take_OK_GET_STATE().take_commands();
+
+                self.state_requested = None;
+
+                for command in commands.iter() {
+                    self.apply_command(command);
+                }
+
+                !commands.is_empty()
+            } else {
+                self.exited = true;
+                true
+            }
+        } else if msg.has_REQ() {
+            let mut req = msg.take_REQ();
+
+            if req.has_INPUT() {
+                let options = self.collect_options(req.get_INPUT());
+                self.input = Some(InputState::new(req.get_tag(), req.take_INPUT(), options));
+                true
+            } else {
+                false
+            }
+        } else if msg.has_EXIT() {
+            // wait GET_STATE error to receive remaining commands
+            self.exit_status = Some(msg.take_EXIT());
+            false
+        } else if msg.has_DEFAULT_RES() || msg.has_WITHDRAW_REQ() {
+            let tag = if msg.has_DEFAULT_RES() {
+                msg.get_DEFAULT_RES().get_tag()
+            } else {
+                msg.get_WITHDRAW_REQ()
+            };
+
+            // program doesn't wait this request anymore, so close its prompt
+            if self.input.as_ref().is_some_and(|input| input.tag == tag) {
+                self.input = None;
+                self.button_from = self.screen.lines().len();
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    }
+
+    fn apply_command(&mut self, command: &ProgramCommand) {
+        self.screen.apply(command);
+
+        let len = self.screen.lines().len();
+
+        if len < self.printed_rows.len() {
+            self.erase_rows += self.printed_rows.drain(len..).sum::<usize>();
+        }
+
+        self.button_from = self.button_from.min(len);
+    }
+
+    fn collect_options(&self, req: &InputRequest) -> Vec<(String, InputResponse)> {
+        if let Some(InputRequest_oneof_data::STR_SELECT(ref options)) = req.data {
+            return options
+                .get_data()
+                .iter()
+                .map(|option| {
+                    let mut value = InputResponse::new();
+                    value.set_STR(option.clone());
+                    (option.clone(), value)
+                })
+                .collect();
+        }
+
+        if req.has_TOUCH() || req.has_ENTER() || req.has_ANYKEY() {
+            return Vec::new();
+        }
+
+        let lines = &self.screen.lines()[self.button_from..];
+
+        let mut options: Vec<_> = lines
+            .iter()
+            .chain(Some(self.screen.current_line()))
+            .flat_map(|line| line.spans.iter())
+            .filter_map(|span| {
+                span.button
+                    .as_ref()
+                    .filter(|value| is_acceptable(req, value))
+                    .map(|value| (span.text.clone(), value.clone()))
+            })
+            .collect();
+
+        if options.is_empty() && req.has_BOOLEAN() {
+            for (text, value) in [("Yes", true), ("No", false)] {
+                let mut res = InputResponse::new();
+                res.set_BOOLEAN(value);
+                options.push((text.into(), res));
+            }
+        }
+
+        options
+    }
+
+    fn redraw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let renderer = TextRenderer::ansi(self.width);
+        let up = self.erase_rows + self.transient_rows;
+
+        if up >= self.height {
+            queue!(
+                out,
+                terminal::Clear(terminal::ClearType::All),
+                cursor::MoveTo(0, 0)
+            )?;
+        } else if up > 0 {
+            queue!(out, cursor::MoveToPreviousLine(up as u16))?;
+        } else {
+            queue!(out, cursor::MoveToColumn(0))?;
+        }
+
+        queue!(out, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+
+        self.erase_rows = 0;
+
+        let lines = &self.screen.lines()[self.printed_rows.len()..];
+
+        for line in lines {
+            queue!(out, Print(renderer.render_line(line)), Print("\r\n"))?;
+            self.printed_rows.push(self.line_rows(line));
+        }
+
+        let mut transient = Vec::new();
+
+        if !self.screen.current_line().is_empty() {
+            transient.push(renderer.render_line(self.screen.current_line()));
+        }
+
+        let mut cursor_column = None;
+
+        if let Some(input) = &self.input {
+            cursor_column = input.render(&mut transient);
+        }
+
+        for (idx, row) in transient.iter().enumerate() {
+            if idx > 0 {
+                queue!(out, Print("\r\n"))?;
+            }
+            queue!(out, Print(row))?;
+        }
+
+        self.transient_rows = transient.len().saturating_sub(1);
+
+        match cursor_column {
+            Some(column) => queue!(out, cursor::MoveToColumn(column as u16), cursor::Show)?,
+            None => queue!(out, cursor::Hide)?,
+        }
+
+        out.flush()
+    }
+
+    fn line_rows(&self, line: &Line) -> usize {
+        let width = if line.rule {
+            self.width
+        } else {
+            line.spans
+                .iter()
+                .map(|span| display_width(&span.text))
+                .sum()
+        };
+
+        if self.width == 0 || width == 0 {
+            1
+        } else {
+            width.div_ceil(self.width)
+        }
+    }
+}
+
+enum KeyAction {
+    None,
+    Redraw,
+    Submit(InputResponse),
+}
+
+struct InputState {
+    tag: u32,
+    req: InputRequest,
+    expire: Option<DateTime<Utc>>,
+    shown_secs: Option<i64>,
+    options: Vec<(String, InputResponse)>,
+    selected: usize,
+    editing: bool,
+    text: Vec<char>,
+    cursor: usize,
+    error: Option<String>,
+}
+
+impl InputState {
+    fn new(tag: u32, req: InputRequest, options: Vec<(String, InputResponse)>) -> Self {
+        let expire = if req.has_expire() {
+            let expire = req.get_expire();
+            Utc.timestamp_opt(expire.seconds, expire.nanos as u32)
+                .single()
+        } else {
+            None
+        };
+
+        Self {
+            tag,
+            expire,
+            shown_secs: None,
+            editing: options.is_empty()
+                && !req.has_TOUCH()
+                && !req.has_ENTER()
+                && !req.has_ANYKEY(),
+            req,
+            options,
+            selected: 0,
+            text: Vec::new(),
+            cursor: 0,
+            error: None,
+        }
+    }
+
+    fn remain_secs(&self) -> Option<i64> {
+        self.expire
+            .map(|expire| (expire - Utc::now()).num_seconds().max(0))
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expire.is_some_and(|expire| Utc::now() >= expire)
+    }
+
+    /// Return true if countdown is changed
+    fn tick(&mut self) -> bool {
+        let secs = self.remain_secs();
+
+        if secs != self.shown_secs {
+            self.shown_secs = secs;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_number_selectable(&self) -> bool {
+        self.options.len() < 10
+            && !self
+                .options
+                .iter()
+                .any(|(_, value)| value.has_INT() || value.has_FLOAT())
+    }
+
+    fn on_key(&mut self, key: KeyEvent) -> KeyAction {
+        if self.req.has_ANYKEY() {
+            return KeyAction::Submit(empty_response());
+        }
+
+        if self.req.has_ENTER() || self.req.has_TOUCH() {
+            return match key.code {
+                KeyCode::Enter | KeyCode::Char(' ') => KeyAction::Submit(empty_response()),
+                _ => KeyAction::None,
+            };
+        }
+
+        if self.editing {
+            self.on_edit_key(key)
+        } else {
+            self.on_select_key(key)
+        }
+    }
+
+    fn on_select_key(&mut self, key: KeyEvent) -> KeyAction {
+        let len = self.options.len();
+
+        match key.code {
+            KeyCode::Up | KeyCode::Left | KeyCode::BackTab => {
+                self.selected = (self.selected + len - 1) % len;
+                KeyAction::Redraw
+            }
+            KeyCode::Down | KeyCode::Right | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % len;
+                KeyAction::Redraw
+            }
+            KeyCode::Enter => KeyAction::Submit(self.options[self.selected].1.clone()),
+            KeyCode::Char(c) if c.is_ascii_digit() && self.is_number_selectable() => {
+                match (c as usize - '0' as usize).checked_sub(1) {
+                    Some(idx) if idx < len => KeyAction::Submit(self.options[idx].1.clone()),
+                    _ => KeyAction::None,
+                }
+            }
+            KeyCode::Char(c) => {
+                self.editing = true;
+                self.text = vec![c];
+                self.cursor = 1;
+                KeyAction::Redraw
+            }
+            _ => KeyAction::None,
+        }
+    }
+
+    fn on_edit_key(&mut self, key: KeyEvent) -> KeyAction {
+        match key.code {
+            KeyCode::Enter => {
+                let text: String = self.text.iter().collect();
+
+                match parse_input(&self.req, &text) {
+                    Ok(value) => return KeyAction::Submit(value),
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            KeyCode::Char(c) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            KeyCode::Esc | KeyCode::Up | KeyCode::Down if !self.options.is_empty() => {
+                self.editing = false;
+                self.text.clear();
+                self.cursor = 0;
+            }
+            KeyCode::Esc => {
+                self.text.clear();
+                self.cursor = 0;
+            }
+            _ => return KeyAction::None,
+        }
+
+        KeyAction::Redraw
+    }
+
+    /// Render input rows and return cursor column of last row
+    fn render(&self, rows: &mut Vec<String>) -> Option<usize> {
+        let mut header = format!("[{}]", input_kind(&self.req));
+
+        if let Some(secs) = self.remain_secs() {
+            header.push_str(&format!(" {}s left", secs));
+        }
+
+        if let Some(error) = &self.error {
+            header.push_str(&format!(" {}", error));
+        }
+
+        rows.push(format!(
+            "{}{}{}",
+            SetAttribute(Attribute::Dim),
+            header,
+            SetAttribute(Attribute::Reset)
+        ));
+
+        if self.req.has_ANYKEY() {
+            rows.push("Press any key".into());
+            return None;
+        }
+
+        if self.req.has_ENTER() || self.req.has_TOUCH() {
+            rows.push("Press Enter".into());
+            return None;
+        }
+
+        let numbered = self.is_number_selectable();
+
+        for (idx, (text, _)) in self.options.iter().enumerate() {
+            let number = if numbered {
+                format!("{}) ", idx + 1)
+            } else {
+                String::new()
+            };
+
+            if !self.editing && idx == self.selected {
+                rows.push(format!(
+                    "> {}{}{}{}",
+                    SetAttribute(Attribute::Reverse),
+                    number,
+                    text,
+                    SetAttribute(Attribute::Reset)
+                ));
+            } else {
+                rows.push(format!("  {}{}", number, text));
+            }
+        }
+
+        if self.editing {
+            let text: String = self.text.iter().collect();
+            let before: String = self.text[..self.cursor].iter().collect();
+            rows.push(format!("> {}", text));
+            Some(2 + display_width(&before))
+        } else {
+            None
+        }
+    }
+}
+
+fn empty_response() -> InputResponse {
+    let mut res = InputResponse::new();
+    res.mut_EMPTY();
+    res
+}
+
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = queue!(io::stdout(), cursor::Show);
+        let _ = io::stdout().flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(static_mut_refs)]
+
 use qni_core_rs::c_api::*;
 use qni_core_rs::prelude::qni_api::*;
 use qni_core_rs::prelude::*;
@@ -158,3 +160,27 @@ fn api_wait_test() {
 
     assert_eq!(msg, connector_ctx.try_get_msg().unwrap());
 }
+
+#[test]
+fn api_exit_code_test() {
+    unsafe {
+        let ctx = qni_console_new();
+        let connector_ctx = ConnectorContext::new((*ctx).clone());
+        let reason = "Crashed: out of memory";
+
+        assert!(connector_ctx.try_get_msg().is_none());
+
+        qni_console_exit_with(ctx, 2, reason.as_ptr(), reason.len());
+        qni_console_exit_with(ctx, 0, "".as_ptr(), 0);
+
+        assert_eq!(1, qni_console_need_exit(ctx));
+        assert_eq!(2, qni_console_exit_code(ctx));
+
+        let status = connector_ctx.try_get_msg().unwrap().take_EXIT();
+        assert_eq!(2, status.get_code());
+        assert_eq!(reason, status.get_reason());
+        assert!(connector_ctx.try_get_msg().is_none());
+
+        qni_console_delete(ctx);
+    }
+}
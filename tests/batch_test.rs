@@ -0,0 +1,101 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use protobuf::{Message, RepeatedField};
+use std::sync::Arc;
+use std::thread;
+
+fn print(text: &str) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT(text.into());
+    command
+}
+
+fn new_line() -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().mut_NEW_LINE();
+    command
+}
+
+#[test]
+fn batch_coalesce_test() {
+    let ctx = ConsoleContext::new();
+    ctx.set_coalesce_prints(true);
+
+    ctx.append_commands(vec![print("Hello"), print(", ")]);
+    ctx.append_command(print("world"));
+    ctx.append_command(new_line());
+    ctx.append_command(print("Bye"));
+
+    assert_eq!(
+        vec![print("Hello, world"), new_line(), print("Bye")],
+        ctx.export_command(0)
+    );
+
+    // observed PRINT isn't changed
+    ctx.append_command(print("!"));
+    assert_eq!(4, ctx.get_command_count());
+    assert_eq!(vec![print("!")], ctx.export_command(3));
+
+    ctx.set_coalesce_prints(false);
+    ctx.append_command(print("a"));
+    ctx.append_command(print("b"));
+    assert_eq!(6, ctx.get_command_count());
+}
+
+#[test]
+fn batch_coalesce_toggle_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for _ in 0..1000 {
+                ctx.append_command(print("a"));
+            }
+        })
+    };
+
+    while !handle.is_finished() {
+        ctx.set_coalesce_prints(true);
+        ctx.set_coalesce_prints(false);
+    }
+
+    handle.join().unwrap();
+
+    // nothing is left pending after coalescing is disabled
+    let text: String = ctx
+        .export_command(0)
+        .iter()
+        .map(|command| command.get_PRINT().get_PRINT())
+        .collect();
+    assert_eq!(1000, text.len());
+}
+
+#[test]
+fn batch_api_test() {
+    unsafe {
+        let ctx = qni_console_new();
+        // qni_print needs other owner of console
+        let _frontend = (*ctx).clone();
+
+        let mut array = ProgramCommandArray::new();
+        array.set_commands(RepeatedField::from_vec(vec![print("a"), new_line()]));
+        let bytes = array.write_to_bytes().unwrap();
+
+        assert_eq!(0, qni_append_commands(ctx, bytes.as_ptr(), bytes.len()));
+        assert_eq!(-1, qni_append_commands(ctx, [0xFF].as_ptr(), 1));
+
+        qni_set_coalesce_prints(ctx, 1);
+        qni_print(ctx, "b".as_ptr(), 1);
+        qni_print(ctx, "c".as_ptr(), 1);
+
+        assert_eq!(
+            vec![print("a"), new_line(), print("bc")],
+            (*ctx).export_command(0)
+        );
+
+        qni_console_delete(ctx);
+    }
+}
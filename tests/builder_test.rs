@@ -0,0 +1,63 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use chrono::prelude::*;
+use std::time::Duration;
+
+#[test]
+fn builder_command_test() {
+    let mut expected = ProgramCommand::new();
+    expected.mut_PRINT().set_PRINT_LINE("Hello".into());
+    assert_eq!(expected, ProgramCommand::print_line("Hello"));
+
+    let mut expected = ProgramCommand::new();
+    let button = expected.mut_PRINT().mut_PRINT_BUTTON();
+    button.set_text("Yes".into());
+    button.mut_value().set_BOOLEAN(true);
+    assert_eq!(expected, ProgramCommand::button("Yes", true));
+
+    let mut expected = ProgramCommand::new();
+    expected.mut_UPDATE_SETTING().set_TEXT_COLOR(0xFFFF0000);
+    assert_eq!(expected, ProgramCommand::text_color(Color::RED));
+
+    let mut font = Font::new();
+    font.set_font_family("Sans".into());
+    font.set_font_size(12.0);
+    font.set_font_style(FontStyle::BOLD as u32);
+    let mut expected = ProgramCommand::new();
+    expected.mut_UPDATE_SETTING().set_FONT(font.clone());
+    assert_eq!(
+        expected,
+        ProgramCommand::font(Font::with_family("Sans", 12.0).style(FontStyle::BOLD))
+    );
+
+    let screen = ScreenState::from_commands(&[
+        ProgramCommand::print("a"),
+        ProgramCommand::new_line(),
+        ProgramCommand::print_line("b"),
+        ProgramCommand::delete_line(1),
+        ProgramCommand::draw_line(),
+    ]);
+    assert_eq!(2, screen.lines().len());
+    assert!(screen.lines()[1].rule);
+}
+
+#[test]
+fn builder_request_test() {
+    let req = ProgramRequest::input_select(vec!["a", "b"]);
+    assert_eq!(&["a", "b"], req.get_INPUT().get_STR_SELECT().get_data());
+
+    let expire = Utc.timestamp_opt(1_000_000, 0).unwrap();
+    let req = ProgramRequest::input_int().expire_at(expire);
+    assert!(req.get_INPUT().has_INT());
+    assert_eq!(1_000_000, req.get_INPUT().get_expire().get_seconds());
+
+    let before = Utc::now().timestamp();
+    let req = ProgramRequest::input_anykey().expire_in(Duration::from_secs(10));
+    assert!(req.get_INPUT().get_expire().get_seconds() >= before + 10);
+
+    let msg = ConsoleMessage::input_response(3, 42);
+    assert_eq!(3, msg.get_RES().get_tag());
+    assert_eq!(42, msg.get_RES().get_OK_INPUT().get_INT());
+    assert_eq!(5, ConsoleMessage::get_state(5).get_REQ().get_GET_STATE());
+}
@@ -0,0 +1,132 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn cancel_wait_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let connector = ConnectorContext::new(ctx.clone());
+    let token = CancelToken::new();
+
+    let handle = {
+        let ctx = ctx.clone();
+        let token = token.clone();
+        thread::spawn(move || {
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+
+            ctx.wait_console_with(req, WaitOptions::new().cancel_token(token))
+        })
+    };
+
+    while ctx.try_get_req().is_none() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let tag = connector.try_get_msg().unwrap().take_REQ().get_tag();
+
+    token.cancel();
+
+    match handle.join().unwrap() {
+        Err(WaitError::Cancelled) => {}
+        other => panic!("expected cancelled but got {:?}", other),
+    }
+
+    assert!(ctx.try_get_req().is_none());
+
+    ctx.set_exit();
+
+    assert_eq!(tag, connector.try_get_msg().unwrap().get_WITHDRAW_REQ());
+    assert!(connector.try_get_msg().unwrap().has_EXIT());
+    assert!(connector.try_get_msg().is_none());
+}
+
+#[test]
+fn cancel_before_send_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let connector = ConnectorContext::new(ctx.clone());
+    let token = CancelToken::new();
+    token.cancel();
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    assert!(matches!(
+        ctx.wait_console_with(req, WaitOptions::new().cancel_token(token)),
+        Err(WaitError::Cancelled)
+    ));
+
+    ctx.set_exit();
+
+    assert_eq!(0, connector.try_get_msg().unwrap().get_WITHDRAW_REQ());
+    assert!(connector.try_get_msg().unwrap().has_EXIT());
+    assert!(connector.try_get_msg().is_none());
+}
+
+#[test]
+fn cancel_late_response_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let token = CancelToken::new();
+    token.cancel();
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    assert!(matches!(
+        ctx.wait_console_with(req, WaitOptions::new().cancel_token(token)),
+        Err(WaitError::Cancelled)
+    ));
+
+    // response of withdrawn request must not be taken by next request
+    let mut res = ConsoleResponse::new();
+    res.set_tag(0);
+    res.mut_OK_INPUT().set_INT(1);
+    ctx.on_recv_response(res);
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    req.mut_INPUT().mut_expire().set_seconds(0);
+    assert!(matches!(ctx.wait_console(req), Err(WaitError::Timeout)));
+}
+
+#[test]
+fn cancel_before_poll_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let connector = ConnectorContext::new(ctx.clone());
+    let token = CancelToken::new();
+    token.cancel();
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    assert!(matches!(
+        ctx.wait_console_with(req, WaitOptions::new().cancel_token(token)),
+        Err(WaitError::Cancelled)
+    ));
+
+    assert_eq!(0, connector.try_get_msg().unwrap().get_WITHDRAW_REQ());
+    assert!(connector.try_get_msg().is_none());
+
+    // connector created later doesn't get stale withdrawal
+    let late = ConnectorContext::new(ctx.clone());
+    assert!(late.try_get_msg().is_none());
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+            ctx.wait_console(req)
+        })
+    };
+
+    while ctx.try_get_req().is_none() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(1, connector.try_get_msg().unwrap().take_REQ().get_tag());
+    assert_eq!(1, late.try_get_msg().unwrap().take_REQ().get_tag());
+
+    ctx.set_exit();
+    assert!(handle.join().unwrap().is_err());
+}
@@ -0,0 +1,166 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use chrono::prelude::*;
+use protobuf::well_known_types::Timestamp;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn clock_manual_test() {
+    let start = Utc.timestamp_opt(1_000_000, 0).unwrap();
+    let clock = ManualClock::with_wall_time(start);
+
+    clock.advance(Duration::from_secs(3));
+
+    assert_eq!(Duration::from_secs(3), clock.now());
+    assert_eq!(start + chrono::Duration::seconds(3), clock.wall_now());
+    assert_eq!(
+        Duration::from_secs(10),
+        clock.to_monotonic(start + chrono::Duration::seconds(10))
+    );
+    assert_eq!(Duration::from_secs(3), clock.to_monotonic(start));
+}
+
+#[test]
+fn clock_wait_timeout_test() {
+    let clock = Arc::new(ManualClock::with_wall_time(
+        Utc.timestamp_opt(1_000_000, 0).unwrap(),
+    ));
+    let ctx = Arc::new(ConsoleContext::with_clock(clock.clone()));
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut expire = Timestamp::new();
+            expire.set_seconds(1_000_010);
+
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+            req.mut_INPUT().set_expire(expire);
+
+            ctx.wait_console(req)
+        })
+    };
+
+    while ctx.try_get_req().is_none() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    clock.advance(Duration::from_secs(9));
+    thread::sleep(Duration::from_millis(20));
+    assert!(!handle.is_finished());
+
+    clock.advance(Duration::from_secs(1));
+
+    match handle.join().unwrap() {
+        Err(WaitError::Timeout) => {}
+        other => panic!("expected timeout but got {:?}", other),
+    }
+}
+
+#[test]
+fn clock_wait_default_test() {
+    let clock = Arc::new(ManualClock::with_wall_time(
+        Utc.timestamp_opt(1_000_000, 0).unwrap(),
+    ));
+    let ctx = Arc::new(ConsoleContext::with_clock(clock.clone()));
+    let connector = ConnectorContext::new(ctx.clone());
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut default = InputResponse::new();
+            default.set_INT(3);
+
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+
+            ctx.wait_console_with(
+                req,
+                WaitOptions::new()
+                    .timeout(Duration::from_secs(5))
+                    .default_response(default),
+            )
+        })
+    };
+
+    while ctx.try_get_req().is_none() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let req = connector.try_get_msg().unwrap().take_REQ();
+    assert_eq!(1_000_005, req.get_INPUT().get_expire().get_seconds());
+    assert!(connector.try_get_msg().unwrap().has_ACCEPT_RES());
+
+    clock.advance(Duration::from_secs(5));
+
+    let res = handle.join().unwrap().unwrap();
+    assert_eq!(3, res.get_OK_INPUT().get_INT());
+
+    let res = connector.try_get_msg().unwrap().take_DEFAULT_RES();
+    assert_eq!(req.get_tag(), res.get_tag());
+    assert_eq!(3, res.get_OK_INPUT().get_INT());
+    assert!(connector.try_get_msg().is_none());
+}
+
+#[test]
+fn clock_wait_huge_timeout_test() {
+    let ctx = Arc::new(ConsoleContext::with_clock(Arc::new(ManualClock::new())));
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+
+            ctx.wait_console_with(req, WaitOptions::new().timeout(Duration::MAX))
+        })
+    };
+
+    let req = loop {
+        if let Some(req) = ctx.try_get_req() {
+            break req;
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert!(!req.get_INPUT().has_expire());
+
+    let mut res = ConsoleResponse::new();
+    res.set_tag(req.get_tag());
+    res.mut_OK_INPUT().set_INT(3);
+    ctx.on_recv_response(res);
+
+    assert_eq!(3, handle.join().unwrap().unwrap().get_OK_INPUT().get_INT());
+}
+
+#[test]
+fn clock_default_before_poll_test() {
+    let ctx = Arc::new(ConsoleContext::with_clock(Arc::new(ManualClock::new())));
+    let connector = ConnectorContext::new(ctx.clone());
+
+    let mut default = InputResponse::new();
+    default.set_INT(3);
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+
+    let res = ctx
+        .wait_console_with(
+            req,
+            WaitOptions::new()
+                .timeout(Duration::ZERO)
+                .default_response(default),
+        )
+        .unwrap();
+    assert_eq!(3, res.get_OK_INPUT().get_INT());
+
+    let res = connector.try_get_msg().unwrap().take_DEFAULT_RES();
+    assert_eq!(0, res.get_tag());
+    assert!(connector.try_get_msg().is_none());
+
+    // connector created later doesn't get stale default response
+    let late = ConnectorContext::new(ctx.clone());
+    assert!(late.try_get_msg().is_none());
+}
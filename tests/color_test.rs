@@ -0,0 +1,60 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+
+#[test]
+fn color_wire_test() {
+    let color = Color::rgba(0x12, 0x34, 0x56, 0x78);
+    assert_eq!(0x78123456, u32::from(color));
+    assert_eq!(color, Color::from(0x78123456));
+    assert_eq!((0x12, 0x34, 0x56, 0x78), color.to_rgba());
+    assert_eq!(0xFF, color.with_alpha(0xFF).a());
+    assert_eq!(0xFFFF0000, Color::RED.to_argb());
+}
+
+#[test]
+fn color_parse_test() {
+    assert_eq!(Ok(Color::rgb(0xFF, 0x80, 0x00)), "#ff8000".parse());
+    assert_eq!(Ok(Color(0x80FF8000)), "80FF8000".parse());
+    assert!("#f80".parse::<Color>().is_err());
+    assert_eq!(Ok(Color::ORANGE), " Orange ".parse());
+    assert_eq!(Ok(Color::CYAN), "aqua".parse());
+    assert_eq!(
+        Err(ColorParseError::Invalid("#12345".into())),
+        "#12345".parse::<Color>()
+    );
+    assert!("+fffff".parse::<Color>().is_err());
+
+    assert_eq!("#ff8000", Color::rgb(0xFF, 0x80, 0x00).to_string());
+    assert_eq!("#80ff8000", Color(0x80FF8000).to_string());
+    assert_eq!("#ff8000", Color(0x80FF8000).to_css());
+    assert_eq!(Some("lime"), Color::LIME.name());
+    assert_eq!(None, Color(0xFF123456).name());
+
+    for (name, color) in Color::PALETTE {
+        assert_eq!(Ok(*color), name.parse());
+        assert_eq!(Ok(*color), color.to_string().parse());
+    }
+}
+
+#[test]
+fn color_api_test() {
+    unsafe {
+        let mut ctx = Arc::new(ConsoleContext::new());
+        let mut out = 0;
+
+        assert_eq!(0, qni_parse_color("navy".as_ptr(), 4, &mut out));
+        assert_eq!(0xFF000080, out);
+        assert_eq!(-1, qni_parse_color("nope".as_ptr(), 4, &mut out));
+
+        assert_eq!(0, qni_set_text_color_str(&mut ctx, "#ff0000".as_ptr(), 7));
+        assert_eq!(-1, qni_set_back_color_str(&mut ctx, "#f0".as_ptr(), 3));
+
+        assert_eq!(
+            vec![ProgramCommand::text_color(Color::RED)],
+            ctx.command_snapshot(0).to_vec()
+        );
+    }
+}
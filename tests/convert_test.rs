@@ -0,0 +1,69 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use chrono::prelude::*;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+#[test]
+fn convert_round_trip_test() {
+    assert_eq!(Ok(3), i32::try_from(InputResponse::from(3)));
+    assert_eq!(Ok(1.5), f32::try_from(InputResponse::from(1.5f32)));
+    assert_eq!(Ok(true), bool::try_from(InputResponse::from(true)));
+    assert_eq!(
+        Ok("abc".to_string()),
+        String::try_from(InputResponse::from("abc"))
+    );
+    assert_eq!(
+        Ok(Color::CYAN),
+        Color::try_from(InputResponse::from(Color::CYAN))
+    );
+
+    let date = NaiveDate::from_ymd_opt(2020, 2, 29).unwrap();
+    assert_eq!(Ok(date), NaiveDate::try_from(InputResponse::from(date)));
+
+    let datetime = Utc.with_ymd_and_hms(2020, 2, 29, 12, 34, 56).unwrap();
+    assert_eq!(
+        Ok(datetime),
+        DateTime::<Utc>::try_from(InputResponse::from(datetime))
+    );
+
+    let time = Duration::from_secs(3600 + 60 + 1);
+    assert_eq!(Ok(time), Duration::try_from(InputResponse::from(time)));
+}
+
+#[test]
+fn convert_error_test() {
+    let err = i32::try_from(InputResponse::from("3")).unwrap_err();
+    assert_eq!(
+        ConvertError::Mismatch {
+            expected: "integer",
+            found: "string",
+        },
+        err
+    );
+    assert_eq!(
+        "expected integer response but found string",
+        err.to_string()
+    );
+
+    let datetime = InputResponse::from(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap());
+    assert!(NaiveDate::try_from(datetime).is_err());
+
+    let mut time = protobuf::well_known_types::Duration::new();
+    time.set_seconds(-1);
+    let mut res = InputResponse::new();
+    res.set_TIME(time);
+    assert_eq!(
+        Err(ConvertError::OutOfRange("time")),
+        Duration::try_from(res)
+    );
+
+    assert_eq!(
+        Err(ConvertError::Mismatch {
+            expected: "boolean",
+            found: "none",
+        }),
+        bool::try_from(InputResponse::new())
+    );
+}
@@ -0,0 +1,114 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn event_subscribe_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let events = ctx.subscribe();
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut command = ProgramCommand::new();
+            command.mut_PRINT().set_PRINT_LINE("Hello".into());
+            ctx.append_command(command);
+
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+            let num = ctx.wait_console(req).unwrap().take_OK_INPUT().get_INT();
+
+            ctx.set_exit_with(num, "done");
+        })
+    };
+
+    match events.recv().unwrap() {
+        ConsoleEvent::Command { index, command } => {
+            assert_eq!(0, index);
+            assert_eq!("Hello", command.get_PRINT().get_PRINT_LINE());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let tag = match events.recv().unwrap() {
+        ConsoleEvent::Request(req) => req.get_tag(),
+        event => panic!("unexpected event {:?}", event),
+    };
+
+    let mut res = ConsoleResponse::new();
+    res.set_tag(tag);
+    res.mut_OK_INPUT().set_INT(3);
+    ctx.on_recv_response(res.clone());
+
+    assert_eq!(ConsoleEvent::Response(res), events.recv().unwrap());
+
+    match events.recv().unwrap() {
+        ConsoleEvent::Exit(Some(status)) => {
+            assert_eq!(3, status.get_code());
+            assert_eq!("done", status.get_reason());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    handle.join().unwrap();
+
+    // exit event is sent only once
+    ctx.set_exit();
+    assert!(events.recv_timeout(Duration::from_millis(50)).is_err());
+}
+
+#[test]
+fn event_listener_test() {
+    let ctx = ConsoleContext::new();
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let id = {
+        let count = count.clone();
+        ctx.add_listener(move |event| {
+            if let ConsoleEvent::Command { .. } = event {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    };
+
+    ctx.append_command(ProgramCommand::new());
+    ctx.append_command(ProgramCommand::new());
+
+    assert!(ctx.remove_listener(id));
+    assert!(!ctx.remove_listener(id));
+
+    ctx.append_command(ProgramCommand::new());
+
+    assert_eq!(2, count.load(Ordering::Relaxed));
+}
+
+#[test]
+fn event_reentrant_listener_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let id = Arc::new(OnceLock::new());
+    let count = Arc::new(AtomicUsize::new(0));
+
+    // listener removes itself, which deadlocks if it is called under lock
+    let listener_id = {
+        let weak = Arc::downgrade(&ctx);
+        let id = id.clone();
+        let count = count.clone();
+        ctx.add_listener(move |_| {
+            count.fetch_add(1, Ordering::Relaxed);
+
+            if let Some(ctx) = weak.upgrade() {
+                assert!(ctx.remove_listener(*id.get().unwrap()));
+            }
+        })
+    };
+    id.set(listener_id).unwrap();
+
+    ctx.append_command(ProgramCommand::new());
+    ctx.append_command(ProgramCommand::new());
+
+    assert_eq!(1, count.load(Ordering::Relaxed));
+}
@@ -0,0 +1,73 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+use qni_core_rs::testing::snapshot_text;
+
+use std::sync::Arc;
+
+fn styled(styles: FontStyles) -> [ProgramCommand; 2] {
+    [
+        ProgramCommand::font(Font::new().style(styles)),
+        ProgramCommand::print_line("text"),
+    ]
+}
+
+#[test]
+fn font_styles_encoding_test() {
+    assert_eq!(FontStyles::ITALIC, FontStyle::ITALIC.into());
+    assert_eq!(FontStyles::BOLD, FontStyle::BOLD.into());
+    assert_eq!(FontStyles::empty(), FontStyle::REGULAR.into());
+    assert_eq!(
+        3,
+        Font::new()
+            .style(FontStyles::BOLD | FontStyles::ITALIC)
+            .font_style
+    );
+    assert_eq!(
+        FontStyle::BOLD as u32,
+        Font::new().style(FontStyle::BOLD).font_style
+    );
+
+    let screen = ScreenState::from_commands(&styled(FontStyles::BOLD | FontStyles::UNDERLINE));
+    assert_eq!(
+        FontStyles::BOLD | FontStyles::UNDERLINE,
+        screen.lines()[0].spans[0].settings.font_styles()
+    );
+}
+
+#[test]
+fn font_styles_render_test() {
+    let all = FontStyles::all();
+
+    assert_eq!(
+        "\x1b[1;3;4;9mtext\x1b[0m\n",
+        TextRenderer::ansi(0).render_commands(&styled(all))
+    );
+
+    let screen = ScreenState::from_commands(&styled(all));
+    assert_eq!(
+        "{bold italic underline strike}text{/}\n",
+        snapshot_text(&screen)
+    );
+
+    let html = HtmlExporter::new()
+        .export_commands(&styled(FontStyles::ITALIC | FontStyles::STRIKETHROUGH));
+    assert!(html.contains("style=\"font-style:italic;text-decoration:line-through;\""));
+}
+
+#[test]
+fn font_styles_api_test() {
+    unsafe {
+        let mut ctx = Arc::new(ConsoleContext::new());
+        let styles = FontStyles::BOLD | FontStyles::STRIKETHROUGH;
+
+        qni_set_font(&mut ctx, "Serif".as_ptr(), 5, 12.0, styles.bits());
+
+        assert_eq!(
+            vec![ProgramCommand::font(
+                Font::with_family("Serif", 12.0).style(styles)
+            )],
+            ctx.command_snapshot(0).to_vec()
+        );
+    }
+}
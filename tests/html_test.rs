@@ -0,0 +1,55 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+#[test]
+fn html_export_test() {
+    let ctx = ConsoleContext::new();
+
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE("<Hello> & bye".into());
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command.mut_UPDATE_SETTING().set_TEXT_COLOR(0xFFFF8000);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command.mut_UPDATE_SETTING().set_HIGHLIGHT_COLOR(0xFF00FF00);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command
+        .mut_UPDATE_SETTING()
+        .set_TEXT_ALIGN(TextAlign::CENTER);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    let button = command.mut_PRINT().mut_PRINT_BUTTON();
+    button.set_text("[1] Yes".into());
+    button.mut_value().set_INT(1);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().mut_DRAW_LINE();
+    ctx.append_command(command);
+
+    let html = HtmlExporter::new().title("bug #1").export_console(&ctx);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>bug #1</title>"));
+    assert!(html.contains("<span>&lt;Hello&gt; &amp; bye</span>"));
+    assert!(html.contains(
+        "<div class=\"qni-line\" style=\"text-align:center\"><span class=\"qni-button\" data-value=\"1\" style=\"color:#ff8000;--qni-highlight:#00ff00;\">[1] Yes</span></div>"
+    ));
+    assert!(html.contains("<hr class=\"qni-rule\">"));
+}
+
+#[test]
+fn html_font_family_escape_test() {
+    let font = Font::with_family("a';color:red;x:'\"<", 0.0);
+    let html = HtmlExporter::new()
+        .export_commands(&[ProgramCommand::font(font), ProgramCommand::print_line("x")]);
+
+    assert!(html
+        .contains("style=\"font-family:&#39;a\\27 ;color:red;x:\\27 \\22 \\3c &#39;,monospace;\""));
+}
@@ -0,0 +1,66 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+#[test]
+fn input_parse_test() {
+    let mut req = InputRequest::new();
+
+    req.mut_INT();
+    assert_eq!(100, parse_input(&req, " 100 ").unwrap().get_INT());
+    assert!(parse_input(&req, "abc").is_err());
+
+    req.set_STR_MAX_LEN(2);
+    assert_eq!("가나", parse_input(&req, "가나").unwrap().get_STR());
+    assert_eq!(
+        Err(InputParseError::TooLong(2)),
+        parse_input(&req, "가나다")
+    );
+
+    req.mut_BOOLEAN();
+    assert!(parse_input(&req, "Yes").unwrap().get_BOOLEAN());
+
+    let mut options = StringArray::new();
+    options.mut_data().push("Fight".into());
+    options.mut_data().push("Run".into());
+    req.set_STR_SELECT(options);
+    assert_eq!("Run", parse_input(&req, "Run").unwrap().get_STR());
+    assert_eq!("Fight", parse_input(&req, "1").unwrap().get_STR());
+    assert!(parse_input(&req, "3").is_err());
+}
+
+#[test]
+fn input_parse_time_test() {
+    let mut req = InputRequest::new();
+
+    req.mut_DATE();
+    assert_eq!(
+        1_577_836_800,
+        parse_input(&req, "2020-01-01").unwrap().get_DATE().seconds
+    );
+
+    req.mut_DATETIME();
+    assert_eq!(
+        1_577_840_400,
+        parse_input(&req, "2020-01-01 01:00")
+            .unwrap()
+            .get_DATETIME()
+            .seconds
+    );
+
+    req.mut_TIME();
+    assert_eq!(
+        3723,
+        parse_input(&req, "01:02:03").unwrap().get_TIME().seconds
+    );
+
+    req.mut_COLOR();
+    assert_eq!(
+        0xFFFF8000,
+        parse_input(&req, "#ff8000").unwrap().get_COLOR()
+    );
+    assert_eq!(
+        0x80FF8000,
+        parse_input(&req, "80ff8000").unwrap().get_COLOR()
+    );
+    assert!(parse_input(&req, "#fff").is_err());
+}
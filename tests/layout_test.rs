@@ -0,0 +1,69 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+
+#[test]
+fn layout_width_test() {
+    assert_eq!(5, display_width("hello"));
+    assert_eq!(4, display_width("한글"));
+    assert_eq!(6, display_width("かな漢"));
+    assert_eq!(4, display_width("ＡＢ"));
+    assert_eq!(1, display_width("e\u{301}"));
+    assert_eq!(7, display_width("a한b글c"));
+    assert_eq!(2, display_width("\u{627}\u{670}\u{dc0}\u{dca}"));
+
+    assert_eq!("a한", truncate_to_width("a한b", 3));
+    assert_eq!("a", truncate_to_width("a한b", 2));
+    assert_eq!("", truncate_to_width("한", 1));
+
+    assert_eq!("한글  ", pad_to_width("한글", 6, TextAlign::LEFT));
+    assert_eq!("  한글", pad_to_width("한글", 6, TextAlign::RIGHT));
+    assert_eq!(" 한글 ", pad_to_width("한글", 6, TextAlign::CENTER));
+    assert_eq!("한 ", pad_to_width("한글", 3, TextAlign::LEFT));
+}
+
+#[test]
+fn layout_table_test() {
+    let table = Table::new()
+        .header(["이름", "HP"])
+        .row(["Alice", "10"])
+        .row(["용사", "120"])
+        .align(1, TextAlign::RIGHT);
+
+    assert_eq!(vec![5, 3], table.column_widths());
+    assert_eq!(
+        vec![
+            ProgramCommand::print_line("이름    HP"),
+            ProgramCommand::print_line("Alice   10"),
+            ProgramCommand::print_line("용사   120"),
+        ],
+        table.commands()
+    );
+
+    let screen = ScreenState::from_commands(&table.border(true).width(0, 4).commands());
+    assert_eq!(
+        "\n| 이름 |  HP |\n\n| Alic |  10 |\n| 용사 | 120 |\n\n",
+        screen.text()
+    );
+}
+
+#[test]
+fn layout_api_test() {
+    unsafe {
+        let mut ctx = Arc::new(ConsoleContext::new());
+        let cells = ["이름", "HP", "용사", "120"];
+        let ptrs: Vec<_> = cells.iter().map(|cell| cell.as_ptr()).collect();
+        let lens: Vec<_> = cells.iter().map(|cell| cell.len()).collect();
+
+        assert_eq!(4, qni_display_width(cells[0].as_ptr(), cells[0].len()));
+
+        qni_print_padded(&mut ctx, "HP".as_ptr(), 2, 4, TextAlign::RIGHT as u32);
+        qni_new_line(&mut ctx);
+        qni_print_table(&mut ctx, ptrs.as_ptr(), lens.as_ptr(), 2, 2, 1, 0);
+
+        let screen = ScreenState::from_commands(&ctx.command_snapshot(0));
+        assert_eq!("  HP\n이름  HP\n용사  120\n", screen.text());
+    }
+}
@@ -0,0 +1,95 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use protobuf::Message;
+use std::sync::Arc;
+use std::thread;
+
+fn command(i: usize) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT(i.to_string());
+    command
+}
+
+fn text(command: &ProgramCommand) -> &str {
+    command.get_PRINT().get_PRINT()
+}
+
+#[test]
+fn log_snapshot_test() {
+    let log = CommandLog::from_commands((0..600).map(command));
+
+    assert_eq!(600, log.len());
+    assert_eq!(Some(command(300)), log.get(300));
+    assert_eq!(None, log.get(600));
+
+    let snapshot = log.snapshot(250);
+    log.append(command(600));
+
+    // snapshot isn't changed by append
+    assert_eq!(350, snapshot.len());
+    assert_eq!("250", text(snapshot.get(0).unwrap()));
+    assert_eq!("599", text(snapshot.get(349).unwrap()));
+    assert!(snapshot
+        .iter()
+        .map(text)
+        .eq((250..600).map(|i| i.to_string())));
+
+    assert!(log.snapshot(601).is_empty());
+    assert!(log.snapshot(1000).is_empty());
+}
+
+#[test]
+fn log_concurrent_test() {
+    let log = Arc::new(CommandLog::new());
+
+    let writer = {
+        let log = log.clone();
+        thread::spawn(move || {
+            for i in 0..5000 {
+                log.append(command(i));
+            }
+        })
+    };
+
+    while !writer.is_finished() {
+        let snapshot = log.snapshot(0);
+
+        for (i, command) in snapshot.iter().enumerate() {
+            assert_eq!(i.to_string(), text(command));
+        }
+    }
+
+    writer.join().unwrap();
+    assert_eq!(5000, log.len());
+}
+
+#[test]
+fn log_encode_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let connector = ConnectorContext::new(ctx.clone());
+
+    for i in 0..300 {
+        let mut command = ProgramCommand::new();
+        command.mut_PRINT().set_PRINT_LINE(i.to_string().repeat(i));
+        ctx.append_command(command);
+    }
+
+    for from in [0, 10, 299, 300] {
+        let mut msg = ConsoleMessage::new();
+        msg.mut_REQ().set_GET_STATE(from);
+
+        let expected = connector
+            .on_recv_message(msg.clone())
+            .unwrap()
+            .write_to_bytes()
+            .unwrap();
+
+        // second one uses cached bytes
+        assert_eq!(
+            expected,
+            connector.on_recv_message_encoded(msg.clone()).unwrap()
+        );
+        assert_eq!(expected, connector.on_recv_message_encoded(msg).unwrap());
+    }
+}
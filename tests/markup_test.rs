@@ -0,0 +1,175 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+
+#[test]
+fn markup_commands_test() {
+    let base = ConsoleSettings {
+        text_color: Some(Color::WHITE.0),
+        ..ConsoleSettings::default()
+    };
+    let commands = parse_markup(
+        "[color=#ff0000]Danger[/color] [b]now[/b] [button=1]Fight[/button]",
+        &base,
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec![
+            ProgramCommand::text_color(Color::RED),
+            ProgramCommand::print("Danger"),
+            ProgramCommand::text_color(Color::WHITE),
+            ProgramCommand::print(" "),
+            ProgramCommand::font(Font::new().style(FontStyles::BOLD)),
+            ProgramCommand::print("now"),
+            ProgramCommand::font(Font::new()),
+            ProgramCommand::print(" "),
+            ProgramCommand::button("Fight", 1),
+        ],
+        commands
+    );
+
+    let commands = parse_markup("[[x] [button=go][[y][/button]", &ConsoleSettings::default());
+    assert_eq!(
+        Ok(vec![
+            ProgramCommand::print("[x] "),
+            ProgramCommand::button("[y]", "go"),
+        ]),
+        commands
+    );
+}
+
+#[test]
+fn markup_font_style_test() {
+    let font = Font::with_family("Serif", 14.0).style(FontStyles::ITALIC);
+    let base = ConsoleSettings {
+        font: Some(font.clone()),
+        ..ConsoleSettings::default()
+    };
+
+    // family and size of current font are kept
+    assert_eq!(
+        Ok(vec![
+            ProgramCommand::font(font.clone().style(FontStyles::ITALIC | FontStyles::BOLD)),
+            ProgramCommand::print("x"),
+            ProgramCommand::font(font),
+        ]),
+        parse_markup("[b]x[/b]", &base)
+    );
+
+    // font which was never set only gets style and is emptied after tag
+    assert_eq!(
+        Ok(vec![
+            ProgramCommand::font(Font::new().style(FontStyles::UNDERLINE)),
+            ProgramCommand::print("x"),
+            ProgramCommand::font(Font::new()),
+        ]),
+        parse_markup("[u]x[/u]", &ConsoleSettings::default())
+    );
+}
+
+#[test]
+fn markup_restore_test() {
+    let console = Console::new(Arc::new(ConsoleContext::new()));
+    console
+        .context()
+        .append_command(ProgramCommand::text_color(Color::BLUE));
+
+    console
+        .print_markup("a[color=red]b[b]c[color=lime]d[/color]e[/b][/color]f")
+        .unwrap();
+
+    let screen = ScreenState::from_commands(&console.context().command_snapshot(0));
+    let spans = &screen.current_line().spans;
+    let style = |index: usize| {
+        (
+            spans[index].text.as_str(),
+            spans[index].settings.text_color.map(Color),
+            spans[index].settings.font_styles(),
+        )
+    };
+
+    assert_eq!(("a", Some(Color::BLUE), FontStyles::empty()), style(0));
+    assert_eq!(("b", Some(Color::RED), FontStyles::empty()), style(1));
+    assert_eq!(("c", Some(Color::RED), FontStyles::BOLD), style(2));
+    assert_eq!(("d", Some(Color::LIME), FontStyles::BOLD), style(3));
+    assert_eq!(("e", Some(Color::RED), FontStyles::BOLD), style(4));
+    assert_eq!(("f", Some(Color::BLUE), FontStyles::empty()), style(5));
+    assert_eq!(
+        Some(Color::BLUE.into()),
+        console.context().settings().text_color
+    );
+}
+
+#[test]
+fn markup_error_test() {
+    let parse = |text| parse_markup(text, &ConsoleSettings::default()).unwrap_err();
+
+    assert_eq!(MarkupError::UnterminatedTag(2), parse("ab[b"));
+    assert_eq!(
+        MarkupError::UnknownTag {
+            tag: "blink".into(),
+            position: 0,
+        },
+        parse("[blink]x[/blink]")
+    );
+    assert_eq!(
+        MarkupError::MissingValue {
+            tag: "color".into(),
+            position: 0,
+        },
+        parse("[color]x[/color]")
+    );
+    assert_eq!(
+        MarkupError::InvalidValue {
+            tag: "color".into(),
+            value: "#zz".into(),
+            position: 0,
+        },
+        parse("[color=#zz]x[/color]")
+    );
+    assert_eq!(
+        MarkupError::MismatchedClose {
+            expected: "i".into(),
+            found: "b".into(),
+            position: 7,
+        },
+        parse("[b][i]x[/b][/i]")
+    );
+    assert_eq!(
+        MarkupError::UnexpectedClose {
+            tag: "b".into(),
+            position: 1,
+        },
+        parse("x[/b]")
+    );
+    assert_eq!(
+        MarkupError::Unclosed {
+            tag: "u".into(),
+            position: 1,
+        },
+        parse("x[u]y")
+    );
+    assert_eq!(
+        MarkupError::TagInButton(10),
+        parse("[button=1][b]x[/b][/button]")
+    );
+    assert_eq!(
+        "expected [/i] but found [/b] at 7",
+        parse("[b][i]x[/b][/i]").to_string()
+    );
+
+    unsafe {
+        let mut ctx = Arc::new(ConsoleContext::new());
+        let text = "[b]x[/i]";
+
+        assert_eq!(-1, qni_print_markup(&mut ctx, text.as_ptr(), text.len()));
+        assert_eq!(0, ctx.get_command_count());
+
+        let text = "[i]x[/i]";
+        assert_eq!(0, qni_print_markup(&mut ctx, text.as_ptr(), text.len()));
+        assert_eq!(3, ctx.get_command_count());
+    }
+}
@@ -0,0 +1,113 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+use qni_core_rs::testing::*;
+
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Fight,
+    Run,
+}
+
+#[test]
+fn menu_select_test() {
+    let mut frontend = MockFrontend::run(|ctx| {
+        let console = Console::new(ctx);
+        let action = console.menu(&[("Fight", Action::Fight), ("Run", Action::Run)]);
+        console.print_line(format!("{:?}", action.unwrap()));
+    });
+
+    // out of range answer is ignored and asked again
+    frontend.expect_int().answer_int(3);
+    frontend.expect_int().answer_int(2);
+    frontend.finish();
+
+    frontend.assert_text("Fight\nRun\nRun\n");
+
+    let screen = frontend.screen();
+    assert_eq!(
+        Some(&InputResponse::from(1)),
+        screen.lines()[0].spans[0].button.as_ref()
+    );
+}
+
+#[test]
+fn menu_layout_test() {
+    let mut frontend = MockFrontend::run(|ctx| {
+        let console = Console::new(ctx);
+        let labels = ["공격", "Defend", "Item"];
+        let options = MenuOptions::new().numbered(true);
+
+        console
+            .menu_index(&labels, options.layout(MenuLayout::Horizontal))
+            .unwrap();
+        console
+            .menu_index(&labels, options.layout(MenuLayout::Grid(2)))
+            .unwrap();
+    });
+
+    frontend.expect_int().answer_int(1);
+    frontend.expect_int().answer_int(1);
+    frontend.finish();
+
+    frontend.assert_text("[1] 공격  [2] Defend  [3] Item\n[1] 공격    [2] Defend\n[3] Item\n");
+}
+
+#[test]
+fn menu_api_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let mut frontend = MockFrontend::attach(ctx.clone());
+
+    let handle = std::thread::spawn(move || unsafe {
+        let mut ctx = ctx;
+        let labels = ["Yes", "No"];
+        let ptrs: Vec<_> = labels.iter().map(|label| label.as_ptr()).collect();
+        let lens: Vec<_> = labels.iter().map(|label| label.len()).collect();
+        let mut index = 0;
+
+        let result = qni_menu(
+            &mut ctx,
+            ptrs.as_ptr(),
+            lens.as_ptr(),
+            2,
+            1,
+            0,
+            0,
+            &mut index,
+        );
+        (result, index)
+    });
+
+    frontend.expect_int().answer_int(2);
+
+    assert_eq!((QniWaitResult::Ok, 1), handle.join().unwrap());
+    frontend.assert_text("Yes  No\n");
+}
+
+#[test]
+fn menu_empty_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let console = Console::new(ctx.clone());
+    let items: [(&str, Action); 0] = [];
+
+    assert!(matches!(console.menu(&items), Err(WaitError::EmptyMenu)));
+    assert_eq!(0, ctx.get_command_count());
+
+    unsafe {
+        let mut ctx = ctx;
+        let mut index = 0;
+        let result = qni_menu(
+            &mut ctx,
+            std::ptr::null(),
+            std::ptr::null(),
+            0,
+            0,
+            0,
+            0,
+            &mut index,
+        );
+        assert_eq!(QniWaitResult::Internal, result);
+    }
+}
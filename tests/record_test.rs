@@ -0,0 +1,142 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn game(ctx: Arc<ConsoleContext>, greeting: &'static str) {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE(greeting.into());
+    ctx.append_command(command);
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    let num = ctx.wait_console(req).unwrap().take_OK_INPUT().get_INT();
+
+    let mut command = ProgramCommand::new();
+    command
+        .mut_PRINT()
+        .set_PRINT_LINE(format!("You chose {}", num));
+    ctx.append_command(command);
+}
+
+fn record_session() -> Recording {
+    let ctx = Arc::new(ConsoleContext::new());
+    let recorder = Arc::new(Recorder::new());
+    ctx.set_recorder(Some(recorder.clone()));
+
+    let handle = {
+        let ctx = ctx.clone();
+        thread::spawn(move || game(ctx, "Hello"))
+    };
+
+    let req = loop {
+        if let Some(req) = ctx.try_get_req() {
+            break req;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let mut res = ConsoleResponse::new();
+    res.set_tag(req.get_tag());
+    res.mut_OK_INPUT().set_INT(3);
+    ctx.on_recv_response(res);
+
+    handle.join().unwrap();
+
+    recorder.recording()
+}
+
+#[test]
+fn record_replay_test() {
+    let recording = record_session();
+
+    assert_eq!(4, recording.entries.len());
+    assert_eq!(2, recording.commands().count());
+    assert_eq!(3, recording.response(0).unwrap().get_OK_INPUT().get_INT());
+
+    let mut buf = Vec::new();
+    recording.write_to(&mut buf).unwrap();
+    let recording = Recording::read_from(&mut buf.as_slice()).unwrap();
+
+    let replayer = Replayer::new(recording).request_timeout(Duration::from_secs(5));
+
+    let report = replayer.replay(|ctx| game(ctx, "Hello"));
+    assert!(report.is_match(), "{}", report);
+
+    let report = replayer.replay(|ctx| game(ctx, "Hi"));
+    assert_eq!(1, report.diffs.len());
+    assert!(matches!(
+        report.diffs[0],
+        ReplayDiff::Command { index: 0, .. }
+    ));
+}
+
+#[test]
+fn record_replay_panic_test() {
+    let recording = record_session();
+    let replayer = Replayer::new(recording).request_timeout(Duration::from_secs(5));
+
+    let report = replayer.replay(|_ctx| panic!("broken program"));
+    assert!(!report.is_match());
+    assert_eq!(Some("broken program"), report.panicked.as_deref());
+}
+
+#[test]
+fn record_replay_clock_test() {
+    fn timed_game(ctx: Arc<ConsoleContext>) {
+        let mut default = InputResponse::new();
+        default.set_INT(3);
+
+        let mut req = ProgramRequest::new();
+        req.mut_INPUT().mut_INT();
+        let num = ctx
+            .wait_console_with(
+                req,
+                WaitOptions::new()
+                    .timeout(Duration::from_secs(5))
+                    .default_response(default),
+            )
+            .unwrap()
+            .take_OK_INPUT()
+            .get_INT();
+
+        ctx.append_command(ProgramCommand::print_line(format!("You chose {}", num)));
+    }
+
+    // recording of restored session starts from later tag
+    let mut req = ProgramRequest::new();
+    req.set_tag(7);
+    req.mut_INPUT().mut_INT();
+
+    let mut res = ConsoleResponse::new();
+    res.set_tag(7);
+    res.mut_OK_INPUT().set_INT(3);
+
+    let recording = Recording {
+        entries: vec![
+            RecordEntry {
+                elapsed: Duration::ZERO,
+                event: RecordEvent::Request(req),
+            },
+            RecordEntry {
+                elapsed: Duration::from_secs(5),
+                event: RecordEvent::Response(res),
+            },
+            RecordEntry {
+                elapsed: Duration::from_secs(5),
+                event: RecordEvent::Command(ProgramCommand::print_line("You chose 3")),
+            },
+        ],
+    };
+
+    let clock = Arc::new(ManualClock::new());
+    let report = Replayer::new(recording)
+        .request_timeout(Duration::from_secs(5))
+        .clock(clock.clone())
+        .replay(timed_game);
+
+    assert!(report.is_match(), "{}", report);
+    assert_eq!(Duration::from_secs(5), clock.now());
+}
@@ -0,0 +1,98 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+use qni_core_rs::transport::*;
+
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn registry_reap_test() {
+    let registry = ConsoleRegistry::new();
+
+    let (first, first_ctx) = registry.create();
+    let (second, second_ctx) = registry.create();
+    assert_eq!(32, first.len());
+    assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(first, second);
+    registry
+        .insert("player", Arc::new(ConsoleContext::new()))
+        .unwrap();
+
+    assert!(registry
+        .insert("player", Arc::new(ConsoleContext::new()))
+        .is_err());
+    let mut ids = vec![first.clone(), second.clone(), "player".to_string()];
+    ids.sort();
+    assert_eq!(ids, registry.list());
+    assert!(Arc::ptr_eq(&first_ctx, &registry.get(&first).unwrap()));
+
+    let connector = ConnectorContext::new(second_ctx.clone());
+    first_ctx.set_exit();
+    second_ctx.set_exit();
+
+    // second console is still connected
+    assert_eq!(vec![first], registry.reap());
+    assert_eq!(1, second_ctx.connector_count());
+
+    drop(connector);
+
+    assert_eq!(vec![second], registry.reap());
+    assert_eq!(vec!["player".to_string()], registry.list());
+}
+
+#[test]
+fn registry_handshake_test() {
+    let registry = Arc::new(ConsoleRegistry::new());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (_, alice) = registry.create();
+    let (bob_id, bob) = registry.create();
+
+    {
+        let registry = registry.clone();
+        thread::spawn(move || serve_registry(listener, registry));
+    }
+
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE("Alice".into());
+    alice.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE("Bob".into());
+    bob.append_command(command);
+
+    let mut transport = StreamTransport::connect_session(addr, &bob_id).unwrap();
+
+    let mut msg = ConsoleMessage::new();
+    msg.mut_REQ().set_GET_STATE(0);
+    transport.send(msg).unwrap();
+
+    let msg = loop {
+        if let Some(msg) = transport.try_recv().unwrap() {
+            break msg;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let commands = msg.get_RES().get_OK_GET_STATE().get_commands();
+    assert_eq!(1, commands.len());
+    assert_eq!("Bob", commands[0].get_PRINT().get_PRINT_LINE());
+
+    // unknown session is closed
+    let mut transport = StreamTransport::connect_session(addr, "unknown").unwrap();
+
+    loop {
+        match transport.try_recv() {
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(err) => {
+                assert_eq!(std::io::ErrorKind::ConnectionAborted, err.kind());
+                break;
+            }
+            Ok(Some(msg)) => panic!("unexpected message {:?}", msg),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+fn print_line(text: &str) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE(text.into());
+    command
+}
+
+fn align(align: TextAlign) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_UPDATE_SETTING().set_TEXT_ALIGN(align);
+    command
+}
+
+#[test]
+fn render_plain_test() {
+    let mut draw_line = ProgramCommand::new();
+    draw_line.mut_PRINT().mut_DRAW_LINE();
+
+    let commands = [
+        print_line("left"),
+        align(TextAlign::RIGHT),
+        print_line("right"),
+        align(TextAlign::CENTER),
+        print_line("mid"),
+        draw_line,
+    ];
+
+    assert_eq!(
+        "left\n     right\n   mid\n----------\n",
+        TextRenderer::plain(10).render_commands(&commands)
+    );
+    assert_eq!(
+        "left\nright\nmid\n\n",
+        TextRenderer::plain(0).render_commands(&commands)
+    );
+}
+
+#[test]
+fn render_ansi_test() {
+    let mut color = ProgramCommand::new();
+    color.mut_UPDATE_SETTING().set_TEXT_COLOR(0xFFFF8000);
+
+    let mut font = ProgramCommand::new();
+    font.mut_UPDATE_SETTING()
+        .mut_FONT()
+        .set_font_style(FontStyle::BOLD as u32);
+
+    let commands = [print_line("plain"), color, font, print_line("styled")];
+
+    assert_eq!(
+        "plain\n\x1b[1;38;2;255;128;0mstyled\x1b[0m\n",
+        TextRenderer::ansi(0).render_commands(&commands)
+    );
+}
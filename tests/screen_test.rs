@@ -0,0 +1,92 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+fn print(text: &str) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT(text.into());
+    command
+}
+
+fn print_line(text: &str) -> ProgramCommand {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE(text.into());
+    command
+}
+
+#[test]
+fn screen_print_test() {
+    let mut screen = ScreenState::new();
+
+    screen.apply(&print("Hello, "));
+    screen.apply(&print_line("world!"));
+    screen.apply(&print("foo\nbar"));
+
+    assert_eq!(3, screen.command_count());
+    assert_eq!(2, screen.lines().len());
+    assert_eq!(1, screen.lines()[0].spans.len());
+    assert_eq!("Hello, world!\nfoo\nbar", screen.text());
+}
+
+#[test]
+fn screen_setting_test() {
+    let mut color = ProgramCommand::new();
+    color.mut_UPDATE_SETTING().set_TEXT_COLOR(0xFFFF0000);
+
+    let mut align = ProgramCommand::new();
+    align.mut_UPDATE_SETTING().set_TEXT_ALIGN(TextAlign::CENTER);
+
+    let mut button = ProgramCommand::new();
+    let button_data = button.mut_PRINT().mut_PRINT_BUTTON();
+    button_data.set_text("[1] Yes".into());
+    button_data.mut_value().set_INT(1);
+
+    let screen = ScreenState::from_commands(&[print("A"), color, align, print("B"), button]);
+
+    let spans = &screen.current_line().spans;
+
+    assert_eq!(3, spans.len());
+    assert_eq!(None, spans[0].settings.text_color);
+    assert_eq!(Some(0xFFFF0000), spans[1].settings.text_color);
+    assert_eq!(TextAlign::CENTER, spans[1].settings.text_align);
+    assert!(spans[2].is_button());
+    assert_eq!(1, spans[2].button.as_ref().unwrap().get_INT());
+    assert_eq!(TextAlign::LEFT, screen.current_line().align());
+    assert_eq!(TextAlign::CENTER, screen.settings().text_align);
+}
+
+#[test]
+fn screen_line_command_test() {
+    let mut new_line = ProgramCommand::new();
+    new_line.mut_PRINT().mut_NEW_LINE();
+
+    let mut draw_line = ProgramCommand::new();
+    draw_line.mut_PRINT().mut_DRAW_LINE();
+
+    let mut delete_line = ProgramCommand::new();
+    delete_line.mut_PRINT().set_DELETE_LINE(2);
+
+    let mut clear_line = ProgramCommand::new();
+    clear_line.mut_PRINT().mut_CLEAR_LINE();
+
+    let mut screen = ScreenState::from_commands(&[
+        print_line("1"),
+        print("2"),
+        new_line,
+        print("3"),
+        draw_line,
+        print("4"),
+    ]);
+
+    assert_eq!(4, screen.lines().len());
+    assert!(screen.lines()[3].rule);
+    assert_eq!(5, screen.iter_lines().count());
+
+    screen.apply(&delete_line);
+
+    assert_eq!("1\n2\n4", screen.text());
+
+    screen.apply(&clear_line);
+
+    assert_eq!("", screen.text());
+    assert_eq!(0, screen.iter_lines().count());
+}
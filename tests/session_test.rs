@@ -0,0 +1,111 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn session_save_restore_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+
+    for text in ["first", "second"] {
+        let mut command = ProgramCommand::new();
+        command.mut_PRINT().set_PRINT_LINE(text.into());
+        ctx.append_command(command);
+    }
+
+    {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+            let _ = ctx.wait_console(req);
+        });
+    }
+
+    while ctx.try_get_req().is_none() {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let snapshot = ctx.snapshot();
+    ctx.set_exit();
+
+    let mut buf = Vec::new();
+    snapshot.write_to(&mut buf).unwrap();
+    assert!(buf.starts_with(b"QNIS"));
+
+    let loaded = ConsoleSnapshot::read_from(&mut buf.as_slice()).unwrap();
+    assert_eq!(snapshot, loaded);
+
+    let clock = Arc::new(ManualClock::new());
+    clock.advance(Duration::from_secs(5));
+    let restored = ConsoleContext::from_snapshot_with_clock(loaded.clone(), clock);
+    assert_eq!(Duration::from_secs(5), restored.clock().now());
+    assert_eq!(2, restored.get_command_count());
+
+    let restored = Arc::new(ConsoleContext::from_snapshot(loaded));
+
+    assert_eq!(2, restored.get_command_count());
+    assert_eq!(1, restored.get_cur_input_tag());
+    assert!(!restored.need_exit());
+    assert_eq!(
+        "second",
+        restored.export_command(1)[0].get_PRINT().get_PRINT_LINE()
+    );
+
+    let connector = ConnectorContext::new(restored);
+    let msg = connector.try_get_msg().unwrap();
+    assert!(msg.get_REQ().get_INPUT().has_INT());
+    assert_eq!(0, msg.get_REQ().get_tag());
+}
+
+#[test]
+fn session_invalid_test() {
+    assert!(matches!(
+        ConsoleSnapshot::read_from(&mut &b"NOPE"[..]),
+        Err(SessionError::InvalidHeader)
+    ));
+
+    let mut buf = Vec::new();
+    ConsoleSnapshot::default().write_to(&mut buf).unwrap();
+    buf[4] = 99;
+
+    assert!(matches!(
+        ConsoleSnapshot::read_from(&mut buf.as_slice()),
+        Err(SessionError::UnsupportedVersion(99))
+    ));
+
+    // huge length of truncated request is not allocated upfront
+    let mut buf = Vec::new();
+    buf.extend_from_slice(b"QNIS");
+    buf.extend_from_slice(&2u32.to_le_bytes());
+    buf.push(0b10);
+    buf.extend_from_slice(&0u64.to_le_bytes());
+    buf.extend_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(matches!(
+        ConsoleSnapshot::read_from(&mut buf.as_slice()),
+        Err(SessionError::Io(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+}
+
+#[test]
+fn session_exit_status_test() {
+    let ctx = ConsoleContext::new();
+    ctx.append_command(ProgramCommand::print_line("bye"));
+    ctx.set_exit_with(3, "game over");
+
+    let mut buf = Vec::new();
+    ctx.snapshot().write_to(&mut buf).unwrap();
+
+    let loaded = ConsoleSnapshot::read_from(&mut buf.as_slice()).unwrap();
+    assert!(loaded.exit);
+    assert_eq!(ctx.exit_status(), loaded.exit_status);
+
+    let restored = ConsoleContext::from_snapshot(loaded);
+    assert!(restored.need_exit());
+    assert_eq!(3, restored.exit_code());
+    assert_eq!("game over", restored.exit_status().unwrap().get_reason());
+    assert_eq!(1, restored.get_command_count());
+}
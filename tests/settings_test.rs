@@ -0,0 +1,125 @@
+use qni_core_rs::c_api::*;
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+
+use std::sync::Arc;
+
+#[test]
+fn settings_stack_test() {
+    let ctx = ConsoleContext::new();
+
+    assert_eq!(None, ctx.text_color());
+    assert_eq!(None, ctx.font());
+    assert!(!ctx.pop_settings());
+
+    ctx.append_command(ProgramCommand::text_color(Color::BLUE));
+    ctx.push_settings();
+
+    ctx.append_commands(vec![
+        ProgramCommand::text_color(Color::RED),
+        ProgramCommand::back_color(Color::WHITE),
+        ProgramCommand::text_align(TextAlign::CENTER),
+    ]);
+    assert_eq!(Some(Color::RED), ctx.text_color());
+    assert_eq!(TextAlign::CENTER, ctx.text_align());
+
+    let count = ctx.get_command_count();
+    assert!(ctx.pop_settings());
+
+    assert_eq!(
+        vec![
+            ProgramCommand::text_color(Color::BLUE),
+            ProgramCommand::text_align(TextAlign::LEFT),
+        ],
+        ctx.command_snapshot(count).to_vec()
+    );
+    assert_eq!(Some(Color::BLUE), ctx.text_color());
+    // color which was never set has no value to restore
+    assert_eq!(Some(Color::WHITE), ctx.back_color());
+
+    // nothing changed, nothing appended
+    ctx.push_settings();
+    assert!(ctx.pop_settings());
+    assert_eq!(count + 2, ctx.get_command_count());
+}
+
+#[test]
+fn settings_guard_test() {
+    let ctx = ConsoleContext::new();
+    let font = Font::with_family("Serif", 14.0).style(FontStyles::BOLD);
+    ctx.append_command(ProgramCommand::highlight_color(Color::WHITE));
+
+    {
+        let _guard = ctx.scoped_settings();
+        ctx.append_command(ProgramCommand::font(font.clone()));
+
+        {
+            let _guard = ctx.scoped_settings();
+            ctx.append_command(ProgramCommand::highlight_color(Color::LIME));
+            assert_eq!(Some(Color::LIME), ctx.highlight_color());
+        }
+
+        assert_eq!(Some(Color::WHITE), ctx.highlight_color());
+        assert_eq!(Some(font), ctx.font());
+    }
+
+    // font which was never set is restored to empty font
+    assert_eq!(Some(Font::new()), ctx.font());
+    assert_eq!(FontStyles::empty(), ctx.settings().font_styles());
+}
+
+#[test]
+fn settings_api_test() {
+    unsafe {
+        let mut ctx = Arc::new(ConsoleContext::new());
+        let mut color = 0;
+        assert_eq!(-1, qni_get_text_color(&mut ctx, &mut color));
+
+        qni_set_text_color(&mut ctx, 0xFF000000);
+        qni_push_settings(&mut ctx);
+        qni_set_text_color(&mut ctx, 0xFF123456);
+        qni_set_font(
+            &mut ctx,
+            "Mono".as_ptr(),
+            4,
+            10.0,
+            FontStyles::ITALIC.bits(),
+        );
+        assert_eq!(0, qni_get_text_color(&mut ctx, &mut color));
+        assert_eq!(0xFF123456, color);
+
+        let (mut family, mut len, mut cap, mut size, mut style) =
+            (std::ptr::null_mut(), 0, 0, 0.0, 0);
+        let ret = qni_get_font(
+            &mut ctx,
+            &mut family,
+            &mut len,
+            &mut cap,
+            &mut size,
+            &mut style,
+        );
+        assert_eq!(0, ret);
+        assert_eq!(b"Mono", std::slice::from_raw_parts(family, len));
+        assert_eq!(10.0, size);
+        assert_eq!(FontStyles::ITALIC.bits(), style);
+        qni_buf_delete(family, len, cap);
+
+        assert_eq!(0, qni_pop_settings(&mut ctx));
+        assert_eq!(-1, qni_pop_settings(&mut ctx));
+        assert_eq!(0, qni_get_text_color(&mut ctx, &mut color));
+        assert_eq!(0xFF000000, color);
+
+        let ret = qni_get_font(
+            &mut ctx,
+            &mut family,
+            &mut len,
+            &mut cap,
+            &mut size,
+            &mut style,
+        );
+        assert_eq!(0, ret);
+        assert_eq!((0, 0.0, 0), (len, size, style));
+        qni_buf_delete(family, len, cap);
+        assert_eq!(TextAlign::LEFT as u32, qni_get_text_align(&mut ctx));
+    }
+}
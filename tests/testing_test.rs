@@ -0,0 +1,126 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+use qni_core_rs::testing::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+fn print_line(ctx: &ConsoleContext, text: &str) {
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().set_PRINT_LINE(text.into());
+    ctx.append_command(command);
+}
+
+fn game(ctx: Arc<ConsoleContext>) {
+    print_line(&ctx, "Choose number");
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_INT();
+    let num = ctx.wait_console(req).unwrap().take_OK_INPUT().get_INT();
+
+    print_line(&ctx, &format!("You chose {}", num));
+
+    let mut req = ProgramRequest::new();
+    req.mut_INPUT().mut_STR();
+    let name = ctx.wait_console(req).unwrap().take_OK_INPUT().take_STR();
+
+    print_line(&ctx, &format!("Bye {}", name));
+}
+
+#[test]
+fn testing_mock_frontend_test() {
+    let mut frontend = MockFrontend::run(game);
+
+    frontend.expect_int().answer_int(100);
+    frontend.expect_str().answer_str("Riey");
+    frontend.finish();
+
+    frontend.assert_text("Choose number\nYou chose 100\nBye Riey\n");
+}
+
+#[test]
+#[should_panic(expected = "expected string request but got integer request")]
+fn testing_wrong_request_test() {
+    let mut frontend = MockFrontend::run(game);
+
+    frontend.expect_str();
+}
+
+#[test]
+#[should_panic(expected = "program finished while expecting request")]
+fn testing_program_finished_test() {
+    let mut frontend = MockFrontend::run(|ctx| print_line(&ctx, "Hello"));
+
+    frontend.expect_int();
+}
+
+#[test]
+fn testing_manual_clock_test() {
+    let mut frontend = MockFrontend::run_with_clock(Arc::new(ManualClock::new()), |ctx| {
+        let mut req = ProgramRequest::new();
+        req.mut_INPUT().mut_INT();
+        let options = WaitOptions::new().timeout(Duration::from_secs(60));
+
+        match ctx.wait_console_with(req, options) {
+            Err(WaitError::Timeout) => print_line(&ctx, "Too late"),
+            _ => print_line(&ctx, "In time"),
+        }
+    });
+
+    frontend.expect_int();
+    frontend.advance(Duration::from_secs(61));
+    frontend.finish();
+
+    frontend.assert_text("Too late\n");
+}
+
+#[test]
+fn testing_diff_test() {
+    assert_eq!("  a\n- b\n+ c\n  d\n", diff_lines("a\nb\nd", "a\nc\nd"));
+}
+
+fn styled(ctx: Arc<ConsoleContext>) {
+    let mut command = ProgramCommand::new();
+    command.mut_UPDATE_SETTING().set_TEXT_COLOR(0xFFFF0000);
+    ctx.append_command(command);
+
+    print_line(&ctx, "Warning {1}");
+
+    let mut command = ProgramCommand::new();
+    command
+        .mut_UPDATE_SETTING()
+        .set_TEXT_ALIGN(TextAlign::CENTER);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    let button = command.mut_PRINT().mut_PRINT_BUTTON();
+    button.set_text("[1] Start".into());
+    button.mut_value().set_INT(1);
+    ctx.append_command(command);
+
+    let mut command = ProgramCommand::new();
+    command.mut_PRINT().mut_DRAW_LINE();
+    ctx.append_command(command);
+}
+
+#[test]
+fn testing_snapshot_text_test() {
+    let mut frontend = MockFrontend::run(styled);
+    frontend.finish();
+
+    assert_eq!(
+        "{fg=#ff0000}Warning {{1}{/}\n{center}{button=1 fg=#ff0000}[1] Start{/}\n---\n",
+        snapshot_text(&frontend.screen())
+    );
+}
+
+#[test]
+fn testing_snapshot_test() {
+    let mut frontend = MockFrontend::run(game);
+
+    frontend.expect_int().answer_int(7);
+    frontend.expect_str().answer_str("Riey");
+    frontend.finish();
+
+    frontend.assert_snapshot("testing_game");
+}
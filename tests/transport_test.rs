@@ -0,0 +1,101 @@
+use qni_core_rs::prelude::qni_api::*;
+use qni_core_rs::prelude::*;
+use qni_core_rs::transport::*;
+
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn recv(transport: &mut impl FrontendTransport) -> ProgramMessage {
+    loop {
+        if let Some(msg) = transport.try_recv().unwrap() {
+            return msg;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn transport_message_test() {
+    let mut msg = ConsoleMessage::new();
+    msg.mut_REQ().set_GET_STATE(10);
+
+    let mut buf = Vec::new();
+    write_message(&mut buf, &msg).unwrap();
+    write_message(&mut buf, &msg).unwrap();
+
+    let mut reader = buf.as_slice();
+
+    assert_eq!(Some(msg.clone()), read_message(&mut reader).unwrap());
+    assert_eq!(Some(msg), read_message(&mut reader).unwrap());
+    assert_eq!(None, read_message::<ConsoleMessage>(&mut reader).unwrap());
+}
+
+#[test]
+fn transport_message_limit_test() {
+    // varint claiming 2^60 bytes without payload
+    let frame = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x10];
+
+    let err = read_message::<ConsoleMessage>(&mut &frame[..]).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+
+    // handshake is limited before peer is known
+    let mut buf = Vec::new();
+    write_handshake(&mut buf, &"x".repeat(4096)).unwrap();
+    let err = read_handshake(&mut buf.as_slice()).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+}
+
+#[test]
+fn transport_tcp_test() {
+    let ctx = Arc::new(ConsoleContext::new());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    {
+        let ctx = ctx.clone();
+        thread::spawn(move || serve_listener(listener, ctx));
+    }
+
+    let program = {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut command = ProgramCommand::new();
+            command.mut_PRINT().set_PRINT_LINE("Hello".into());
+            ctx.append_command(command);
+
+            let mut req = ProgramRequest::new();
+            req.mut_INPUT().mut_INT();
+            ctx.wait_console(req).unwrap().take_OK_INPUT().get_INT()
+        })
+    };
+
+    let mut transport = StreamTransport::connect(addr).unwrap();
+
+    let req = recv(&mut transport);
+    assert!(req.get_REQ().get_INPUT().has_INT());
+
+    let mut msg = ConsoleMessage::new();
+    msg.mut_REQ().set_GET_STATE(0);
+    transport.send(msg).unwrap();
+
+    loop {
+        let msg = recv(&mut transport);
+
+        if msg.has_RES() {
+            let commands = msg.get_RES().get_OK_GET_STATE().get_commands();
+            assert_eq!("Hello", commands[0].get_PRINT().get_PRINT_LINE());
+            break;
+        }
+    }
+
+    let mut msg = ConsoleMessage::new();
+    let res = msg.mut_RES();
+    res.set_tag(req.get_REQ().get_tag());
+    res.mut_OK_INPUT().set_INT(42);
+    transport.send(msg).unwrap();
+
+    assert_eq!(42, program.join().unwrap());
+}